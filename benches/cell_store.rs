@@ -0,0 +1,112 @@
+//! Sweeps over the cells of a grid kept in a `Layout<Cell>` and in a
+//! `CellStore`, on the grid and on a larger world. Run with
+//! `cargo bench --bench cell_store`.
+
+use std::{hint::black_box, time::Instant};
+
+use celleyor::{Cell, CellStore, Layout, SIZE_GRID};
+use nalgebra::Vector2;
+
+const RUNS: u32 = 20;
+
+fn time(name: &str, mut f: impl FnMut() -> f32) {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    println!("{name:<36} {:>10.1?}", start.elapsed() / RUNS);
+}
+
+fn cell(i: usize) -> Cell {
+    let mut cell = Cell::default();
+    cell.color = [(i % 3) as f32 / 2.0, 0.5, 1.0];
+    cell
+}
+
+/// Slots filled in a scattered pattern, about a third of them.
+fn is_filled(x: usize, y: usize) -> bool {
+    (x * 7 + y * 13) % 3 == 1
+}
+
+fn main() {
+    let mut layout: Layout<Cell> = Layout::new();
+    let mut store = CellStore::new(SIZE_GRID[0], SIZE_GRID[1]);
+    for x in 0..SIZE_GRID[0] {
+        for y in 0..SIZE_GRID[1] {
+            if is_filled(x, y) {
+                let pos = Vector2::new(x, y);
+                layout[pos] = Some(cell(x + y));
+                store.insert(pos, cell(x + y));
+            }
+        }
+    }
+
+    println!("{}x{} slots", SIZE_GRID[0], SIZE_GRID[1]);
+    time("layout: sum", || {
+        layout.iter().flatten().flatten().map(|c| c.color[0]).sum()
+    });
+    time("store: sum, cell by cell", || {
+        store
+            .positions()
+            .filter_map(|pos| store.get(pos))
+            .map(|c| c.color[0])
+            .sum()
+    });
+    time("store: sum, by column", || {
+        store.columns().color.iter().map(|color| color[0]).sum()
+    });
+    time("layout: fade", || {
+        for x in 0..SIZE_GRID[0] {
+            for cell in layout[x].iter_mut().flatten() {
+                cell.color[0] *= 0.99;
+            }
+        }
+        0.0
+    });
+    time("store: fade", || {
+        for color in store.columns_mut().color.iter_mut() {
+            color[0] *= 0.99;
+        }
+        0.0
+    });
+
+    // `Layout` has the size of the grid, so the larger world keeps its slots
+    // in a vector instead.
+    let (width, height) = (1000, 1000);
+    let mut slots: Vec<Option<Cell>> = vec![None; width * height];
+    let mut store = CellStore::new(width, height);
+    for (i, slot) in slots.iter_mut().enumerate() {
+        let (x, y) = (i / height, i % height);
+        if is_filled(x, y) {
+            *slot = Some(cell(x + y));
+            store.insert(Vector2::new(x, y), cell(x + y));
+        }
+    }
+
+    println!("{width}x{height} slots");
+    time("slots: sum", || {
+        slots.iter().flatten().map(|c| c.color[0]).sum()
+    });
+    time("store: sum, cell by cell", || {
+        store
+            .positions()
+            .filter_map(|pos| store.get(pos))
+            .map(|c| c.color[0])
+            .sum()
+    });
+    time("store: sum, by column", || {
+        store.columns().color.iter().map(|color| color[0]).sum()
+    });
+    time("slots: fade", || {
+        for cell in slots.iter_mut().flatten() {
+            cell.color[0] *= 0.99;
+        }
+        0.0
+    });
+    time("store: fade", || {
+        for color in store.columns_mut().color.iter_mut() {
+            color[0] *= 0.99;
+        }
+        0.0
+    });
+}
@@ -0,0 +1,54 @@
+//! Observations of every cell of a crowded grid, the sensing done by every cell
+//! on every tick, with the cost per cell scaled up to a 1000x1000 world. Run with
+//! `cargo bench --bench observe`.
+
+use std::{hint::black_box, time::Instant};
+
+use celleyor::{Cell, Grid, SIZE_GRID};
+use nalgebra::Vector2;
+
+const RUNS: u32 = 20;
+
+fn main() {
+    let mut grid = Grid::new();
+    for x in 0..SIZE_GRID[0] {
+        for y in 0..SIZE_GRID[1] {
+            let pos = Vector2::new(x, y);
+            grid.nutrients[pos] = ((x * 31 + y * 17) % 23) as f32;
+            grid.minerals[pos] = ((x * 13 + y * 29) % 19) as f32;
+            if (x * 7 + y * 13) % 3 != 0 {
+                let mut cell = Cell::default();
+                cell.color = [(x % 3) as f32 / 2.0, 0.5, 1.0];
+                grid.spawn_cell(pos, cell);
+            }
+        }
+    }
+    let positions = grid.cell_positions();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for &pos in &positions {
+            if let Some(cell) = grid.cells.get(pos) {
+                let observation = cell.observe(pos, &grid);
+                black_box(observation.gradients);
+                black_box(observation.mean_kinship());
+            }
+        }
+    }
+    let tick = start.elapsed() / RUNS;
+    let per_cell = tick / positions.len() as u32;
+
+    println!(
+        "{} cells of {}x{} slots",
+        positions.len(),
+        SIZE_GRID[0],
+        SIZE_GRID[1]
+    );
+    println!("{:<36} {:>10.1?}", "observe: every cell", tick);
+    println!("{:<36} {:>10.1?}", "observe: one cell", per_cell);
+    println!(
+        "{:<36} {:>10.1?}",
+        "observe: 1000x1000 cells, estimated",
+        per_cell * 1_000_000
+    );
+}
@@ -0,0 +1,14 @@
+#version 330 core
+
+in vec2 ST;
+in vec3 color_bond;
+
+out vec4 fragColor;
+
+void main(void) {
+    float d = abs(ST.y - 0.5);
+    float alpha = smoothstep(0.5, 0.3, d);
+    vec3 col = color_bond * (0.6 + 0.4 * smoothstep(0.5, 0.0, d));
+
+    fragColor = vec4(col, alpha);
+}
\ No newline at end of file
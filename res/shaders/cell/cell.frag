@@ -7,13 +7,67 @@
 #define ANGLE_FOR_BALLS 360.0/COUNT_BALLS
 #define PI 3.14
 
+// Discriminants of `CellType`.
+#define STEM 0
+#define LEAF 1
+#define ROOT 2
+#define MOVER 3
+#define DEFENDER 4
+#define REPRODUCTIVE 5
+
 in vec2 ST;
 in vec3 color_cell;
+in float remains_cell;
+in float type_cell;
 
 uniform float u_time;
 uniform vec2 u_resolution;
 
+// Shrivelled, faded blob of dead matter; `amount` sets its size.
+vec4 remains(vec2 uv, float amount) {
+    vec2 center = vec2(0.5);
+    float angle = atan(uv.y - center.y, uv.x - center.x);
+    float radius = MAX_RADIUS_CELL * (0.4 + 0.6 * amount) * (0.85 + 0.15 * sin(angle * 5.0));
+    float d = distance(uv, center);
+
+    float alpha = smoothstep(radius, radius - 0.05, d) * 0.6;
+    vec3 col = color_cell * (0.7 + 0.3 * smoothstep(0.0, radius, d));
+    return vec4(col, alpha);
+}
+
+// Green chloroplast discs spread over the cytoplasm.
+vec3 leaf(vec2 uv, vec3 col) {
+    vec2 cell = fract(uv * 5.0) - 0.5;
+    float disc = smoothstep(0.22, 0.17, length(cell * vec2(1.0, 1.6)));
+    return mix(col, vec3(0.2, 0.75, 0.25), 0.25 + 0.45 * disc);
+}
+
+// Alpha of thin hairs sticking out of the membrane; `count` of them, `reach` long.
+float hairs(vec2 uv, float d, float count, float reach, float phase) {
+    float angle = atan(uv.y - 0.5, uv.x - 0.5);
+    float hair = smoothstep(0.9, 1.0, abs(sin(angle * count + phase)));
+    return hair * smoothstep(MAX_RADIUS_CELL + reach, MAX_RADIUS_CELL, d)
+        * step(MAX_RADIUS_CELL - 0.02, d);
+}
+
+// Alpha of thorns growing out of a thickened membrane.
+float thorns(vec2 uv, float d) {
+    float angle = atan(uv.y - 0.5, uv.x - 0.5);
+    float radius = MAX_RADIUS_CELL + 0.05 * pow(abs(cos(angle * 4.0)), 6.0);
+    return smoothstep(radius, radius - 0.01, d) * step(MAX_RADIUS_CELL - 0.06, d);
+}
+
+// Draws an appendage of the given colour and alpha behind the body of the cell.
+vec4 appendage(vec4 col, vec3 color, float alpha) {
+    return vec4(mix(col.rgb, color, alpha * (1.0 - col.a)), max(col.a, alpha));
+}
+
 void main(void) {
+    if (remains_cell > 0.0) {
+        gl_FragColor = remains(ST, remains_cell);
+        return;
+    }
+
     float time = u_time * SPEED;
     float radius_cell = MAX_RADIUS_CELL;
     float contrast = 0.006;
@@ -53,9 +107,29 @@ void main(void) {
         col -= vec4(color_cell*vec3(0.5) * alpha, 1.0) - smoothstep(RADIUS_BALLS, 0.03, db)/10.0;
     }
 
+    int cell_type = int(type_cell + 0.5);
+    if (cell_type == LEAF) {
+        col.rgb = leaf(uv, col.rgb);
+    } else if (cell_type == ROOT) {
+        col.rgb = mix(col.rgb, vec3(0.45, 0.3, 0.15), 0.35);
+    } else if (cell_type == DEFENDER) {
+        col.rgb *= 0.6 + 0.4 * smoothstep(MAX_RADIUS_CELL - 0.06, MAX_RADIUS_CELL - 0.12, d);
+    } else if (cell_type == REPRODUCTIVE) {
+        float pulse = 0.5 + 0.5 * sin(time * 6.0);
+        col.rgb += vec3(0.25, 0.15, 0.2) * pulse * smoothstep(MAX_RADIUS_CELL * 0.4, 0.0, d);
+    }
+
     radius_cell = MAX_RADIUS_CELL;
     alpha = smoothstep(radius_cell + beta, radius_cell - contrast + beta, d);
     col.a = alpha;
 
+    if (cell_type == ROOT) {
+        col = appendage(col, vec3(0.45, 0.3, 0.15), hairs(uv, d, 12.0, 0.05, 0.0));
+    } else if (cell_type == MOVER) {
+        col = appendage(col, color_cell * 0.8, hairs(uv, d, 8.0, 0.04, time * 8.0));
+    } else if (cell_type == DEFENDER) {
+        col = appendage(col, color_cell * 0.4, thorns(uv, d));
+    }
+
     gl_FragColor = col;
 }
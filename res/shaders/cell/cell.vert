@@ -3,9 +3,13 @@
 layout (location = 0) in vec2 pos_vertex;
 layout (location = 1) in vec2 st;
 layout (location = 2) in vec3 color;
+layout (location = 3) in float remains;
+layout (location = 4) in float cell_type;
 
 out vec2 ST;
 out vec3 color_cell;
+out float remains_cell;
+out float type_cell;
 
 uniform vec2 u_resolution;
 uniform vec2 u_camera_pos;
@@ -35,4 +39,6 @@ void main(void) {
     gl_Position = vec4(pos);
     ST = st;
     color_cell = color;
+    remains_cell = remains;
+    type_cell = cell_type;
 }
\ No newline at end of file
@@ -1,13 +1,22 @@
 #version 330 core
 
+#define KIND_SOIL 0
+#define KIND_WATER 1
+#define KIND_WALL 2
+#define KIND_LIGHT_SOURCE 3
+#define KIND_TOXIC 4
+#define NIGHT_BRIGHTNESS 0.35
+
 in vec2 St;
 in vec3 Color;
 in float Ltrb;
+in float Kind;
 
 out vec4 fragColor;
 
 uniform vec2 u_resolution;
 uniform float u_camera_scale;
+uniform float u_daylight;
 
 float rand(vec2 co){
     return fract(sin(dot(co, vec2(12.9898, 78.233))) * 43758.5453);
@@ -17,6 +26,34 @@ bool check_bits(int in_bits, int check_bits) {
     return (in_bits & check_bits) == check_bits;
 }
 
+vec4 soil(vec4 color, vec2 st) {
+    float grain = step(0.7, rand(floor(st * 8.0)));
+    return color * vec4(vec3(0.5 + 0.5 * grain), 1.0);
+}
+
+vec4 water(vec4 color, vec2 st, float periodicity) {
+    float wave = sin(st.x * periodicity + sin(st.y * periodicity) * 1.5);
+    return color * vec4(vec3(0.6 + 0.4 * smoothstep(0.5, 1.0, wave)), 1.0);
+}
+
+vec4 wall(vec4 color, vec2 st) {
+    float row = floor(st.y * 4.0);
+    float bx = fract(st.x * 2.0 + mod(row, 2.0) * 0.5);
+    float by = fract(st.y * 4.0);
+    float mortar = max(step(bx, 0.06), step(by, 0.1));
+    return color * vec4(vec3(1.0 - mortar * 0.5), 1.0);
+}
+
+vec4 light_source(vec4 color, vec2 st) {
+    float d = distance(st, vec2(0.5));
+    return color * vec4(vec3(0.4 + smoothstep(0.5, 0.0, d)), 1.0);
+}
+
+vec4 toxic(vec4 color, vec2 st) {
+    float stripe = step(0.5, fract((st.x + st.y) * 3.0));
+    return mix(vec4(0.1, 0.1, 0.1, 1.0), color, stripe);
+}
+
 void main(void)
 {
     vec4 color = vec4(Color, 1.0);
@@ -24,10 +61,23 @@ void main(void)
     vec2 st = St;
     float periodicity = 3.14*4.0;
     int ltrb = int(Ltrb);
+    int kind = int(Kind + 0.5);
     
     float rand_cof = rand(st.xy)*2.0;
     vec4 col =  color * 
         vec4(smoothstep(0.8 - 2.0/(u_camera_scale * 4.0), 0.9, cos(st.x*periodicity - st.y*periodicity+rand_cof/5.0)));
+
+    if (kind == KIND_SOIL) {
+        col = soil(color, st);
+    } else if (kind == KIND_WATER) {
+        col = water(color, st, periodicity);
+    } else if (kind == KIND_WALL) {
+        col = wall(color, st);
+    } else if (kind == KIND_LIGHT_SOURCE) {
+        col = light_source(color, st);
+    } else if (kind == KIND_TOXIC) {
+        col = toxic(color, st);
+    }
        
     if (st.x < border_size + rand_cof/1000.0 && !check_bits(ltrb, 1)) {
         col = color;
@@ -44,9 +94,10 @@ void main(void)
     if (st.y < border_size + rand_cof/1000.0 && !check_bits(ltrb, 8)) {
         col = color;
     }
-    
-    
-    
+
+    if (kind != KIND_LIGHT_SOURCE) {
+        col.rgb *= mix(NIGHT_BRIGHTNESS, 1.0, u_daylight);
+    }
         
     fragColor = col;
 }
\ No newline at end of file
@@ -4,6 +4,7 @@ layout (location = 0) in vec2 pos_vertex;
 layout (location = 1) in vec2 st;
 layout (location = 2) in vec3 zone_color;
 layout (location = 3) in float borders;
+layout (location = 4) in float zone_kind;
 
 uniform vec2 u_resolution;
 uniform vec2 u_camera_pos;
@@ -12,6 +13,7 @@ uniform float u_camera_scale;
 out vec2 St;
 out vec3 Color;
 out float Ltrb;
+out float Kind;
 
 void main(void) {
     vec4 n_cam_pos = vec4(u_camera_pos.xy / u_resolution.xy, 0.0, 1.0);
@@ -37,4 +39,5 @@ void main(void) {
     St = st;
     Color = zone_color;
     Ltrb = borders;
+    Kind = zone_kind;
 }
\ No newline at end of file
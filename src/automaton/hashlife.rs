@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector2;
+
+use crate::{
+    cell::Cell,
+    grid::{constants::SIZE_GRID, layout::Layout, Grid},
+};
+
+use super::LifeRule;
+
+/// Nodes and memoised results kept by default before the caches are dropped.
+pub const MEMO_LIMIT: usize = 1 << 20;
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+/// Slot beyond the edge of the grid or blocked by a wall; dead for good.
+const WALL: NodeId = 2;
+
+/// Square of `2^level` slots; leaves of level `0` are single slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    level: u8,
+    population: u64,
+    /// Quadrants indexed by `x + 2 * y`: lower-left, lower-right, upper-left, upper-right.
+    children: [NodeId; 4],
+}
+
+/// Gosper's HashLife: a life-like automaton over a hash-consed quadtree that
+/// advances `2^n` generations in one step by memoising the future of every node.
+///
+/// The world is the grid: slots beyond its edge and slots blocked by walls are
+/// leaves of their own kind that count as dead and never come alive, so a leap
+/// of `2^n` generations ends where `2^n` calls to [`LifeRule::step`] do.
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    rule: LifeRule,
+    nodes: Vec<Node>,
+    interned: HashMap<[NodeId; 4], NodeId>,
+    /// Centre of a node advanced by `2^j` generations, by node and `j`.
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// Walled-off node of every level.
+    walls: Vec<NodeId>,
+    root: NodeId,
+    /// Position of the lower-left corner of the root.
+    origin: (i64, i64),
+    pub generation: u64,
+    /// Most nodes and results kept in memory. Results beyond it are forgotten;
+    /// a leap making more nodes than that is split in two, see [`Self::advance`].
+    pub memo_limit: usize,
+    /// Whether the leap under way made more nodes than allowed and is given up.
+    aborted: bool,
+}
+
+impl HashLife {
+    /// World holding the cells and walls of the grid.
+    pub fn from_grid(grid: &Grid, rule: LifeRule) -> Self {
+        let mut hashlife = Self {
+            rule,
+            nodes: vec![],
+            interned: HashMap::new(),
+            results: HashMap::new(),
+            walls: vec![],
+            root: DEAD,
+            origin: (0, 0),
+            generation: 0,
+            memo_limit: MEMO_LIMIT,
+            aborted: false,
+        };
+        hashlife.clear_nodes();
+
+        let size = SIZE_GRID[0].max(SIZE_GRID[1]);
+        let level = size.next_power_of_two().trailing_zeros().max(3) as u8;
+        hashlife.root = hashlife.build(grid, level, 0, 0);
+        hashlife
+    }
+
+    /// Layout of the live slots, filled with default cells.
+    pub fn to_layout(&self) -> Layout<Cell> {
+        let mut layout = Layout::new();
+        let (x, y) = self.origin;
+        self.visit(self.root, x, y, &mut |pos| {
+            layout[pos] = Some(Cell::default())
+        });
+        layout
+    }
+
+    /// Advances the world by `2^log_generations` generations.
+    ///
+    /// A leap that makes more than `memo_limit` nodes is given up and made as two
+    /// leaps half as long, with the nodes collected in between; single
+    /// generations are made whatever they take.
+    pub fn advance(&mut self, log_generations: u8) {
+        if self.nodes.len() > self.memo_limit / 2 {
+            self.collect();
+        }
+
+        // A step keeps the inner half of the root, so the grid must lie there.
+        while self.level(self.root) < log_generations + 2 || !self.grid_in_centre() {
+            self.expand();
+        }
+
+        let level = self.level(self.root);
+        let root = self.successor(self.root, log_generations);
+        if std::mem::take(&mut self.aborted) {
+            self.collect();
+            self.advance(log_generations - 1);
+            self.advance(log_generations - 1);
+            return;
+        }
+
+        self.root = root;
+        let margin = 1 << (level - 2);
+        self.origin = (self.origin.0 + margin, self.origin.1 + margin);
+        self.generation += 1 << log_generations;
+    }
+
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn child(&self, id: NodeId, quadrant: usize) -> NodeId {
+        self.nodes[id as usize].children[quadrant]
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.interned.get(&children) {
+            return id;
+        }
+
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.level(children[0]) + 1,
+            population: children
+                .iter()
+                .map(|&child| self.nodes[child as usize].population)
+                .sum(),
+            children,
+        });
+        self.interned.insert(children, id);
+        id
+    }
+
+    fn wall(&mut self, level: u8) -> NodeId {
+        while self.walls.len() <= level as usize {
+            let below = self.walls[self.walls.len() - 1];
+            let wall = self.join([below; 4]);
+            self.walls.push(wall);
+        }
+        self.walls[level as usize]
+    }
+
+    fn grid_in_centre(&self) -> bool {
+        let quarter = 1i64 << (self.level(self.root) - 2);
+        let (x, y) = self.origin;
+        x + quarter <= 0
+            && y + quarter <= 0
+            && x + 3 * quarter >= SIZE_GRID[0] as i64
+            && y + 3 * quarter >= SIZE_GRID[1] as i64
+    }
+
+    /// Doubles the root with walls around, keeping the old one in the middle.
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let e = self.wall(level - 1);
+        let [c0, c1, c2, c3] = self.nodes[self.root as usize].children;
+        let quadrants = [
+            self.join([e, e, e, c0]),
+            self.join([e, e, c1, e]),
+            self.join([e, c2, e, e]),
+            self.join([c3, e, e, e]),
+        ];
+        self.root = self.join(quadrants);
+
+        let margin = 1 << (level - 1);
+        self.origin = (self.origin.0 - margin, self.origin.1 - margin);
+    }
+
+    /// Centre of the node, half its size, advanced by `2^j` generations; `j` is
+    /// at most the level of the node minus two.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        // The nodes made from here on are garbage, soon collected.
+        if self.aborted {
+            return DEAD;
+        }
+        let level = self.level(id);
+        let j = j.min(level - 2);
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+        if j > 0 && self.nodes.len() > self.memo_limit {
+            self.aborted = true;
+            return DEAD;
+        }
+
+        let result = if level == 2 {
+            self.successor_of_leaves(id)
+        } else {
+            let grandchild = |x: usize, y: usize| {
+                self.child(self.child(id, x / 2 + 2 * (y / 2)), x % 2 + 2 * (y % 2))
+            };
+            let grandchildren: [[NodeId; 4]; 4] =
+                std::array::from_fn(|y| std::array::from_fn(|x| grandchild(x, y)));
+
+            // Futures of the nine overlapping squares half the size of the node.
+            let mut parts = [[DEAD; 3]; 3];
+            for y in 0..3 {
+                for x in 0..3 {
+                    let square = self.join([
+                        grandchildren[y][x],
+                        grandchildren[y][x + 1],
+                        grandchildren[y + 1][x],
+                        grandchildren[y + 1][x + 1],
+                    ]);
+                    parts[y][x] = self.successor(square, j);
+                }
+            }
+
+            let mut quadrants = [DEAD; 4];
+            for (i, quadrant) in quadrants.iter_mut().enumerate() {
+                let (x, y) = (i % 2, i / 2);
+                let around = [
+                    parts[y][x],
+                    parts[y][x + 1],
+                    parts[y + 1][x],
+                    parts[y + 1][x + 1],
+                ];
+                *quadrant = if j == level - 2 {
+                    let square = self.join(around);
+                    self.successor(square, j)
+                } else {
+                    // The parts are already as far in time as asked; take their
+                    // corners facing the middle.
+                    let corners = std::array::from_fn(|k| self.child(around[k], 3 - k));
+                    self.join(corners)
+                };
+            }
+            self.join(quadrants)
+        };
+
+        if self.aborted {
+            return DEAD;
+        }
+        if self.results.len() >= self.memo_limit {
+            self.results.clear();
+        }
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Next generation of the middle of a square of four by four slots.
+    fn successor_of_leaves(&mut self, id: NodeId) -> NodeId {
+        let slots: [[NodeId; 4]; 4] = std::array::from_fn(|y| {
+            std::array::from_fn(|x| {
+                let child = self.child(id, x / 2 + 2 * (y / 2));
+                self.child(child, x % 2 + 2 * (y % 2))
+            })
+        });
+        let alive = slots.map(|row| row.map(|slot| slot == ALIVE));
+
+        let next = |x: usize, y: usize| {
+            if slots[y][x] == WALL {
+                return WALL;
+            }
+            let neighbours = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && alive[ny][nx])
+                .count();
+            let survives = if alive[y][x] {
+                self.rule.survival[neighbours]
+            } else {
+                self.rule.birth[neighbours]
+            };
+            if survives {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+        let leaves = [next(1, 1), next(2, 1), next(1, 2), next(2, 2)];
+        self.join(leaves)
+    }
+
+    fn build(&mut self, grid: &Grid, level: u8, x: usize, y: usize) -> NodeId {
+        if x >= SIZE_GRID[0] || y >= SIZE_GRID[1] {
+            return self.wall(level);
+        }
+        if level == 0 {
+            let pos = Vector2::new(x, y);
+            return if grid.is_blocked(pos) {
+                WALL
+            } else if grid.cells.contains(pos) {
+                ALIVE
+            } else {
+                DEAD
+            };
+        }
+
+        let half = 1 << (level - 1);
+        let children = [
+            self.build(grid, level - 1, x, y),
+            self.build(grid, level - 1, x + half, y),
+            self.build(grid, level - 1, x, y + half),
+            self.build(grid, level - 1, x + half, y + half),
+        ];
+        self.join(children)
+    }
+
+    /// Calls `f` for every live slot of the node.
+    fn visit(&self, id: NodeId, x: i64, y: i64, f: &mut impl FnMut(Vector2<usize>)) {
+        let node = self.nodes[id as usize];
+        let size = 1i64 << node.level;
+        let outside =
+            x >= SIZE_GRID[0] as i64 || y >= SIZE_GRID[1] as i64 || x + size <= 0 || y + size <= 0;
+        if node.population == 0 || outside {
+            return;
+        }
+        if node.level == 0 {
+            f(Vector2::new(x as usize, y as usize));
+            return;
+        }
+
+        let half = size / 2;
+        for (quadrant, &child) in node.children.iter().enumerate() {
+            let (dx, dy) = ((quadrant % 2) as i64 * half, (quadrant / 2) as i64 * half);
+            self.visit(child, x + dx, y + dy, f);
+        }
+    }
+
+    /// Drops every node but the leaves and the results.
+    fn clear_nodes(&mut self) {
+        self.nodes = vec![
+            Node {
+                level: 0,
+                population: 0,
+                children: [DEAD; 4],
+            },
+            Node {
+                level: 0,
+                population: 1,
+                children: [DEAD; 4],
+            },
+            Node {
+                level: 0,
+                population: 0,
+                children: [DEAD; 4],
+            },
+        ];
+        self.interned.clear();
+        self.results.clear();
+        self.walls = vec![WALL];
+    }
+
+    /// Keeps only the nodes the root is made of.
+    fn collect(&mut self) {
+        let old = std::mem::take(&mut self.nodes);
+        self.clear_nodes();
+
+        let mut copied = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE), (WALL, WALL)]);
+        self.root = self.copy(&old, self.root, &mut copied);
+    }
+
+    fn copy(&mut self, old: &[Node], id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if let Some(&new) = copied.get(&id) {
+            return new;
+        }
+
+        let children = old[id as usize]
+            .children
+            .map(|child| self.copy(old, child, copied));
+        let new = self.join(children);
+        copied.insert(id, new);
+        new
+    }
+}
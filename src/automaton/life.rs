@@ -0,0 +1,234 @@
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    str::FromStr,
+};
+
+use nalgebra::Vector2;
+
+use crate::{
+    cell::Cell,
+    grid::{bands::map_bands, chunks::ActiveChunks, Grid},
+};
+
+/// Well-known life-like rules by name.
+pub const PRESETS: [(&str, &str); 5] = [
+    ("Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Maze", "B3/S12345"),
+];
+
+/// Life-like rule: the neighbour counts, out of the eight surrounding slots, at
+/// which a dead slot comes alive and a live cell survives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+/// Changes made by one generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Generation {
+    pub births: u64,
+    pub deaths: u64,
+}
+
+impl LifeRule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: [false, false, false, true, false, false, false, false, false],
+        survival: [false, false, true, true, false, false, false, false, false],
+    };
+
+    /// Advances the cells of the grid by one generation on up to `threads` threads,
+    /// visiting only the active chunks. Slots beyond the edge and slots blocked by
+    /// walls count as dead and never come alive.
+    pub fn step(&self, grid: &mut Grid, threads: usize, chunks: &mut ActiveChunks) -> Generation {
+        let mut hasher = DefaultHasher::new();
+        ("life", self).hash(&mut hasher);
+        chunks.prepare(hasher.finish(), &mut grid.edited);
+
+        // Births carry their cell, deaths carry nothing.
+        let changes = map_bands(threads, |band| {
+            let mut changes = vec![];
+            for pos in chunks.slots(band) {
+                let neighbours = moore_neighbours(pos)
+                    .filter(|&n| grid.cells.contains(n))
+                    .count();
+
+                if grid.cells.contains(pos) {
+                    if !self.survival[neighbours] {
+                        changes.push((pos, None));
+                    }
+                } else if self.birth[neighbours] && !grid.is_blocked(pos) {
+                    changes.push((pos, Some(newborn(grid, pos))));
+                }
+            }
+            changes
+        });
+
+        let mut generation = Generation::default();
+        for (pos, cell) in changes {
+            match cell {
+                Some(cell) => {
+                    grid.spawn_cell(pos, cell);
+                    generation.births += 1;
+                }
+                None => {
+                    grid.remove_cell(pos);
+                    generation.deaths += 1;
+                }
+            }
+        }
+
+        generation
+    }
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+/// Parses `B3/S23` style strings; the parts may come in either order, letters in
+/// any case, and `S23/B3` or the bare `23/3` (survival first) are accepted too.
+impl FromStr for LifeRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.trim().split('/').map(str::trim).collect();
+        let [first, second] = parts[..] else {
+            return Err(format!(
+                "expected two parts separated by `/`, like `B3/S23`, found `{}`",
+                s.trim()
+            ));
+        };
+
+        let prefixed = |part: &str| part.chars().next().map(|c| c.to_ascii_uppercase());
+        let (birth, survival) = match (prefixed(first), prefixed(second)) {
+            (Some('B'), _) | (_, Some('S')) => (first, second),
+            (Some('S'), _) | (_, Some('B')) => (second, first),
+            // Classic notation without letters lists survival first.
+            _ => (second, first),
+        };
+
+        Ok(Self {
+            birth: parse_counts(birth, 'B')?,
+            survival: parse_counts(survival, 'S')?,
+        })
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+fn parse_counts(part: &str, letter: char) -> Result<[bool; 9], String> {
+    let digits = part
+        .strip_prefix(letter)
+        .or_else(|| part.strip_prefix(letter.to_ascii_lowercase()))
+        .unwrap_or(part);
+
+    let mut counts = [false; 9];
+    for c in digits.chars() {
+        match c.to_digit(10) {
+            Some(n) if n <= 8 => counts[n as usize] = true,
+            _ => {
+                return Err(format!(
+                    "`{c}` in `{part}` is not a neighbour count; use digits 0 to 8"
+                ))
+            }
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Positions of the eight surrounding slots that lie inside the grid.
+pub(super) fn moore_neighbours(pos: Vector2<usize>) -> impl Iterator<Item = Vector2<usize>> {
+    (-1..=1)
+        .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .filter_map(move |(dx, dy)| Grid::shifted(pos, dx, dy))
+}
+
+/// Cell born into `pos`: it takes the mean colour of its live neighbours.
+fn newborn(grid: &Grid, pos: Vector2<usize>) -> Cell {
+    let parents: Vec<_> = moore_neighbours(pos)
+        .filter_map(|n| grid.cells.get(n))
+        .collect();
+
+    let mut cell = Cell::default();
+    if !parents.is_empty() {
+        cell.color = std::array::from_fn(|i| {
+            parents.iter().map(|parent| parent.color[i]).sum::<f32>() / parents.len() as f32
+        });
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<LifeRule, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn reads_every_notation() {
+        for notation in ["B3/S23", "S23/B3", "23/3", "b3/s23", " B3 / S23 ", "s23/b3"] {
+            assert_eq!(parse(notation), Ok(LifeRule::CONWAY), "{notation}");
+        }
+
+        let seeds = parse("B2/S").unwrap();
+        assert_eq!(seeds.survival, [false; 9]);
+        assert_eq!(parse("/2"), Ok(seeds));
+        assert_eq!(parse("S/B2"), Ok(seeds));
+    }
+
+    #[test]
+    fn rejects_counts_beyond_eight() {
+        assert_eq!(
+            parse("B39/S23"),
+            Err("`9` in `B39` is not a neighbour count; use digits 0 to 8".to_string())
+        );
+        assert_eq!(
+            parse("23/3x"),
+            Err("`x` in `3x` is not a neighbour count; use digits 0 to 8".to_string())
+        );
+        assert_eq!(
+            parse("B3/S23/C2"),
+            Err(
+                "expected two parts separated by `/`, like `B3/S23`, found `B3/S23/C2`".to_string()
+            )
+        );
+        assert!(parse("B3S23").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        assert_eq!(LifeRule::CONWAY.to_string(), "B3/S23");
+        assert_eq!(parse("23/36").unwrap().to_string(), "B36/S23");
+
+        for (_, notation) in PRESETS {
+            assert_eq!(parse(notation).unwrap().to_string(), notation);
+        }
+        for notation in ["B/S", "B012345678/S012345678", "B0/S8"] {
+            let rule = parse(notation).unwrap();
+            assert_eq!(rule.to_string(), notation);
+            assert_eq!(parse(&rule.to_string()), Ok(rule));
+        }
+    }
+}
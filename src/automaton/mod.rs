@@ -0,0 +1,9 @@
+pub use hashlife::HashLife;
+pub use life::LifeRule;
+pub use pattern::Pattern;
+pub use table::RuleTable;
+
+pub mod hashlife;
+pub mod life;
+pub mod pattern;
+pub mod table;
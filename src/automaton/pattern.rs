@@ -0,0 +1,347 @@
+use std::{fmt::Write, str::FromStr};
+
+use nalgebra::Vector2;
+
+use crate::{
+    cell::Cell,
+    grid::{store::CellStore, Grid},
+};
+
+/// Longest line of the RLE written by [`Pattern::to_rle`].
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Two-state Life pattern, as stored in `.rle` and `.cells` files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    /// Live slots as columns from the left and rows from the top.
+    pub live: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    /// Parses a run-length encoded pattern.
+    pub fn from_rle(text: &str) -> Result<Self, String> {
+        let mut pattern = Pattern::default();
+        let mut header = false;
+        let (mut column, mut row) = (0, 0);
+        let mut count = String::new();
+
+        'lines: for (i, line) in text.lines().enumerate() {
+            let error = |message: String| format!("line {}: {message}", i + 1);
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(name) = comment.strip_prefix('N') {
+                    pattern.name = Some(name.trim().to_string());
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if !header {
+                (pattern.width, pattern.height) = parse_rle_header(line).map_err(error)?;
+                header = true;
+                continue;
+            }
+
+            for c in line.chars() {
+                if c.is_ascii_digit() {
+                    count.push(c);
+                    continue;
+                }
+                if c.is_whitespace() {
+                    continue;
+                }
+
+                let run = if count.is_empty() {
+                    1
+                } else {
+                    count
+                        .parse::<usize>()
+                        .map_err(|_| error(format!("run of `{count}` is too long")))?
+                };
+                count.clear();
+                match c {
+                    'b' | '.' => column += run,
+                    'o' | 'A' => {
+                        pattern
+                            .live
+                            .extend((column..column + run).map(|x| (x, row)));
+                        column += run;
+                    }
+                    '$' => {
+                        row += run;
+                        column = 0;
+                    }
+                    '!' => break 'lines,
+                    _ => {
+                        return Err(error(format!(
+                            "`{c}` is not a two-state cell; use `b`, `o`, `$` and `!`"
+                        )))
+                    }
+                }
+            }
+        }
+
+        if !header {
+            return Err("expected a header like `x = 3, y = 3`".to_string());
+        }
+        pattern.fit();
+        Ok(pattern)
+    }
+
+    /// Parses a plaintext pattern: `!` starts a comment, `.` is dead and `O` alive.
+    pub fn from_cells(text: &str) -> Result<Self, String> {
+        let mut pattern = Pattern::default();
+        let mut row = 0;
+        for (i, line) in text.lines().enumerate() {
+            if let Some(comment) = line.strip_prefix('!') {
+                if let Some(name) = comment.strip_prefix("Name:") {
+                    pattern.name = Some(name.trim().to_string());
+                }
+                continue;
+            }
+
+            for (column, c) in line.trim_end().chars().enumerate() {
+                match c {
+                    '.' => {}
+                    'O' | '*' => pattern.live.push((column, row)),
+                    _ => return Err(format!("line {}: `{c}` is neither `.` nor `O`", i + 1)),
+                }
+            }
+            row += 1;
+        }
+
+        pattern.height = row;
+        pattern.fit();
+        Ok(pattern)
+    }
+
+    /// Occupied slots of the area between two corners, both included.
+    pub fn from_area(cells: &CellStore, a: Vector2<usize>, b: Vector2<usize>) -> Self {
+        let (left, right) = (a.x.min(b.x), a.x.max(b.x));
+        let (bottom, top) = (a.y.min(b.y), a.y.max(b.y));
+
+        let mut pattern = Pattern {
+            name: None,
+            width: right - left + 1,
+            height: top - bottom + 1,
+            live: vec![],
+        };
+        for y in (bottom..=top).rev() {
+            for x in left..=right {
+                if cells.contains(Vector2::new(x, y)) {
+                    pattern.live.push((x - left, top - y));
+                }
+            }
+        }
+        pattern
+    }
+
+    /// Places a copy of `cell` on every live slot, with the top-left corner of
+    /// the pattern at `corner`. Slots beyond the grid or blocked by walls are skipped.
+    pub fn stamp(&self, grid: &mut Grid, corner: Vector2<usize>, cell: &Cell) {
+        for &(column, row) in &self.live {
+            let pos = Grid::shifted(corner, column as isize, -(row as isize));
+            let Some(pos) = pos.filter(|&pos| !grid.is_blocked(pos)) else {
+                continue;
+            };
+            grid.spawn_cell(pos, cell.clone());
+        }
+    }
+
+    /// Encodes the pattern as RLE, naming the rule in the header if given.
+    pub fn to_rle(&self, rule: Option<&str>) -> String {
+        let mut rle = String::new();
+        if let Some(name) = &self.name {
+            let _ = writeln!(rle, "#N {name}");
+        }
+        let _ = write!(rle, "x = {}, y = {}", self.width, self.height);
+        if let Some(rule) = rule {
+            let _ = write!(rle, ", rule = {rule}");
+        }
+        rle.push('\n');
+
+        let mut rows = vec![vec![]; self.height];
+        for &(column, row) in &self.live {
+            rows[row].push(column);
+        }
+
+        // Runs of equal tags; trailing dead slots and empty rows are left implicit.
+        let mut runs: Vec<(usize, char)> = vec![];
+        let mut push = |run: usize, tag: char| match runs.last_mut() {
+            Some((count, last)) if *last == tag => *count += run,
+            _ => runs.push((run, tag)),
+        };
+        for (i, columns) in rows.iter_mut().enumerate() {
+            columns.sort_unstable();
+            let mut column = 0;
+            for &live in columns.iter() {
+                if live > column {
+                    push(live - column, 'b');
+                }
+                push(1, 'o');
+                column = live + 1;
+            }
+            if i + 1 < self.height {
+                push(1, '$');
+            }
+        }
+        while runs.last().is_some_and(|&(_, tag)| tag == '$') {
+            runs.pop();
+        }
+
+        let mut line = String::new();
+        let tokens = runs.iter().map(|&(count, tag)| match count {
+            1 => tag.to_string(),
+            _ => format!("{count}{tag}"),
+        });
+        for token in tokens.chain(std::iter::once("!".to_string())) {
+            if line.len() + token.len() > RLE_LINE_LENGTH {
+                rle.push_str(&line);
+                rle.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        }
+        rle.push_str(&line);
+        rle.push('\n');
+        rle
+    }
+
+    /// Grows the size to take in every live slot.
+    fn fit(&mut self) {
+        for &(column, row) in &self.live {
+            self.width = self.width.max(column + 1);
+            self.height = self.height.max(row + 1);
+        }
+    }
+}
+
+/// Reads RLE when the first line past the comments is an `x = ...` header and
+/// plaintext otherwise.
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let is_rle = s
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+            .is_some_and(|line| line.starts_with('x'));
+        if is_rle {
+            Self::from_rle(s)
+        } else {
+            Self::from_cells(s)
+        }
+    }
+}
+
+/// Width and height given by a header like `x = 3, y = 3, rule = B3/S23`.
+fn parse_rle_header(line: &str) -> Result<(usize, usize), String> {
+    let mut size = (None, None);
+    for item in line.split(',') {
+        let Some((key, value)) = item.split_once('=') else {
+            return Err(format!(
+                "expected `key = value` in the header, found `{item}`"
+            ));
+        };
+        let value = value.trim();
+        let dimension = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("`{value}` is not a size"))
+        };
+        match key.trim() {
+            "x" => size.0 = Some(dimension()?),
+            "y" => size.1 = Some(dimension()?),
+            _ => {}
+        }
+    }
+
+    match size {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(format!(
+            "expected a header like `x = 3, y = 3`, found `{line}`"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    #[test]
+    fn reads_rle() {
+        let glider = Pattern::from_rle(GLIDER).unwrap();
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!((glider.width, glider.height), (3, 3));
+        assert_eq!(glider.live, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+
+        // Runs of rows skip empty ones, and runs may be wrapped onto new lines.
+        let spread = Pattern::from_rle("x = 4, y = 6\n2o2$\nb\n2o\n$o2\n$3b\no!").unwrap();
+        assert_eq!(
+            spread.live,
+            [(0, 0), (1, 0), (1, 2), (2, 2), (0, 3), (3, 5)]
+        );
+        assert_eq!((spread.width, spread.height), (4, 6));
+    }
+
+    #[test]
+    fn rle_round_trips() {
+        let mut wide = Pattern {
+            name: Some("Wide".to_string()),
+            width: 200,
+            height: 4,
+            live: vec![],
+        };
+        wide.live.extend((0..200).step_by(3).map(|x| (x, 0)));
+        wide.live.extend((0..200).step_by(7).map(|x| (x, 3)));
+
+        for pattern in [Pattern::from_rle(GLIDER).unwrap(), wide] {
+            let rle = pattern.to_rle(Some("B3/S23"));
+            assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+            assert_eq!(Pattern::from_rle(&rle), Ok(pattern.clone()));
+            assert_eq!(rle.parse(), Ok(pattern));
+        }
+    }
+
+    #[test]
+    fn reads_plaintext() {
+        let text = "!Name: Sparse\n! A comment.\n.O\n\nO.O\n";
+        let pattern: Pattern = text.parse().unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Sparse"));
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.live, [(1, 0), (0, 2), (2, 2)]);
+
+        assert_eq!(
+            Pattern::from_cells("!\n.O\n.x\n"),
+            Err("line 3: `x` is neither `.` nor `O`".to_string())
+        );
+    }
+
+    #[test]
+    fn areas_read_top_down_and_stamp_back() {
+        let mut grid = Grid::new();
+        let cells = [(10, 12), (11, 12), (10, 10), (12, 11)];
+        for (x, y) in cells {
+            grid.spawn_cell(Vector2::new(x, y), Cell::default());
+        }
+
+        let pattern = Pattern::from_area(&grid.cells, Vector2::new(12, 10), Vector2::new(10, 12));
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.live, [(0, 0), (1, 0), (2, 1), (0, 2)]);
+
+        let mut copy = Grid::new();
+        pattern.stamp(&mut copy, Vector2::new(10, 12), &Cell::default());
+        let mut expected: Vec<_> = cells.map(|(x, y)| Vector2::new(x, y)).to_vec();
+        expected.sort_by_key(|pos| (pos.x, pos.y));
+        let mut stamped = copy.cell_positions();
+        stamped.sort_by_key(|pos| (pos.x, pos.y));
+        assert_eq!(stamped, expected);
+    }
+}
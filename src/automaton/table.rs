@@ -0,0 +1,874 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    str::FromStr,
+};
+
+use nalgebra::Vector2;
+
+use crate::{
+    cell::Cell,
+    grid::{bands::map_bands, chunks::ActiveChunks, Grid},
+};
+
+use super::life::Generation;
+
+/// Rule tables bundled with the game.
+pub const PRESETS: [(&str, &str); 2] = [
+    ("Wireworld", include_str!("../../res/rules/Wireworld.rule")),
+    (
+        "Brian's Brain",
+        include_str!("../../res/rules/BriansBrain.rule"),
+    ),
+];
+
+/// Most states a rule table can have.
+pub const MAX_STATES: usize = 256;
+/// Most neighbourhoods whose next state a table remembers; the cache starts over
+/// once it would grow past this.
+pub const CACHE_LIMIT: usize = 1 << 16;
+
+/// Offsets of the neighbours in the order of Golly transitions: N, NE, E, SE, S,
+/// SW, W, NW for Moore and N, E, S, W for von Neumann; the grid's `y` grows upwards.
+const MOORE: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+const VON_NEUMANN: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Moore,
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Moore => &MOORE,
+            Neighbourhood::VonNeumann => &VON_NEUMANN,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetries {
+    None,
+    Rotate4,
+    Rotate8,
+    ReflectHorizontal,
+    Rotate4Reflect,
+    Rotate8Reflect,
+    Permute,
+}
+
+/// Input or output of a transition: a state, or a variable over a set of states.
+///
+/// As in Golly, a variable used several times in one transition takes the same
+/// value everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Term {
+    State(u8),
+    Var(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transition {
+    /// The centre followed by the neighbours.
+    inputs: Vec<Term>,
+    output: Term,
+}
+
+/// Multi-state automaton read from a Golly `.rule` file with a `@TABLE` section.
+///
+/// State `0` is an empty slot; every other state is a cell drawn in the colour
+/// of its state. Slots matching no transition keep their state.
+#[derive(Debug, Clone)]
+pub struct RuleTable {
+    pub name: String,
+    pub n_states: usize,
+    pub neighbourhood: Neighbourhood,
+    pub symmetries: Symmetries,
+    /// Colour of every state.
+    pub colors: Vec<[f32; 3]>,
+    /// Sets of states of the variables, as flags per state.
+    vars: Vec<Vec<bool>>,
+    transitions: Vec<Transition>,
+    /// Orders in which the neighbours are read to apply the symmetries, except
+    /// for [`Symmetries::Permute`].
+    orders: Vec<Vec<usize>>,
+    /// Next state of the neighbourhoods looked up lately, at most [`CACHE_LIMIT`].
+    cache: HashMap<Vec<u8>, u8>,
+    /// Hash of the source text, telling tables apart.
+    fingerprint: u64,
+}
+
+/// Tables are equal if they have the same rules, whatever they have cached.
+impl PartialEq for RuleTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint
+            && self.name == other.name
+            && self.n_states == other.n_states
+            && self.neighbourhood == other.neighbourhood
+            && self.symmetries == other.symmetries
+            && self.colors == other.colors
+            && self.vars == other.vars
+            && self.transitions == other.transitions
+    }
+}
+
+impl RuleTable {
+    /// Advances the cells of the grid by one generation on up to `threads` threads,
+    /// visiting only the active chunks. Slots beyond the edge count as state `0`;
+    /// slots blocked by walls keep their state.
+    pub fn step(
+        &mut self,
+        grid: &mut Grid,
+        threads: usize,
+        chunks: &mut ActiveChunks,
+    ) -> Generation {
+        chunks.prepare(self.fingerprint, &mut grid.edited);
+
+        // Every band looks up the neighbourhoods it meets for the first time on its
+        // own; they join the cache once the bands are done.
+        let table = &*self;
+        let bands = map_bands(threads, |band| {
+            let offsets = table.neighbourhood.offsets();
+            let mut changes = vec![];
+            let mut found = HashMap::new();
+            let mut key = Vec::with_capacity(offsets.len() + 1);
+            for pos in chunks.slots(band) {
+                key.clear();
+                key.push(table.state(grid, pos));
+                key.extend(offsets.iter().map(|&(dx, dy)| {
+                    Grid::shifted(pos, dx, dy).map_or(0, |n| table.state(grid, n))
+                }));
+
+                let next = table.next_state(&mut key, &mut found);
+                if next != key[0] && !grid.is_blocked(pos) {
+                    changes.push((pos, next));
+                }
+            }
+            vec![(changes, found)]
+        });
+
+        let mut all_changes = vec![];
+        for (changes, found) in bands {
+            all_changes.extend(changes);
+            self.remember(found);
+        }
+
+        let mut generation = Generation::default();
+        for (pos, state) in all_changes {
+            if state == 0 {
+                grid.remove_cell(pos);
+                generation.deaths += 1;
+                continue;
+            }
+
+            let color = self.colors[state as usize];
+            match grid.cells.get_mut(pos) {
+                Some(cell) => {
+                    *cell.state = state;
+                    *cell.color = color;
+                    grid.edited.mark(pos);
+                }
+                None => {
+                    let cell = Cell {
+                        state,
+                        color,
+                        ..Cell::default()
+                    };
+                    grid.spawn_cell(pos, cell);
+                    generation.births += 1;
+                }
+            }
+        }
+
+        generation
+    }
+
+    /// Adds neighbourhoods to the cache, emptying it first if they would not fit.
+    fn remember(&mut self, found: HashMap<Vec<u8>, u8>) {
+        if self.cache.len() + found.len() > CACHE_LIMIT {
+            self.cache.clear();
+        }
+        self.cache.extend(found);
+    }
+
+    /// State of the slot; cells in states the table lacks count as empty.
+    fn state(&self, grid: &Grid, pos: Vector2<usize>) -> u8 {
+        let state = grid.cells.get(pos).map_or(0, |cell| cell.state);
+        if (state as usize) < self.n_states {
+            state
+        } else {
+            0
+        }
+    }
+
+    /// Next state of the centre of `key`, which holds the centre and the neighbours.
+    /// Neighbourhoods missing from the cache are looked up in and added to `found`.
+    fn next_state(&self, key: &mut Vec<u8>, found: &mut HashMap<Vec<u8>, u8>) -> u8 {
+        let centre = key[0];
+        if self.symmetries == Symmetries::Permute {
+            key[1..].sort_unstable();
+        }
+        if let Some(&next) = self.cache.get(key.as_slice()) {
+            return next;
+        }
+        if let Some(&next) = found.get(key.as_slice()) {
+            return next;
+        }
+
+        let next = self
+            .transitions
+            .iter()
+            .find_map(|transition| self.apply(transition, key))
+            .unwrap_or(centre);
+        found.insert(key.clone(), next);
+        next
+    }
+
+    /// Output of the transition if it matches the cells under any of the symmetries.
+    fn apply(&self, transition: &Transition, cells: &[u8]) -> Option<u8> {
+        let mut bound = vec![None; self.vars.len()];
+        if !self.bind(transition.inputs[0], cells[0], &mut bound) {
+            return None;
+        }
+
+        let neighbours = &transition.inputs[1..];
+        let matched = if self.symmetries == Symmetries::Permute {
+            let mut used = vec![false; neighbours.len()];
+            self.permuted(neighbours, &cells[1..], &mut used, &mut bound)
+        } else {
+            self.orders.iter().any(|order| {
+                let mut attempt = bound.clone();
+                let matched = neighbours
+                    .iter()
+                    .zip(order)
+                    .all(|(&term, &i)| self.bind(term, cells[1 + i], &mut attempt));
+                if matched {
+                    bound = attempt;
+                }
+                matched
+            })
+        };
+
+        if !matched {
+            return None;
+        }
+        match transition.output {
+            Term::State(state) => Some(state),
+            Term::Var(var) => bound[var],
+        }
+    }
+
+    /// Matches the terms against any arrangement of the unused cells, backtracking.
+    fn permuted(
+        &self,
+        terms: &[Term],
+        cells: &[u8],
+        used: &mut [bool],
+        bound: &mut Vec<Option<u8>>,
+    ) -> bool {
+        let Some((&term, rest)) = terms.split_first() else {
+            return true;
+        };
+
+        for i in 0..cells.len() {
+            // Equal cells are interchangeable; trying the first unused one is enough.
+            if used[i] || (i > 0 && cells[i] == cells[i - 1] && !used[i - 1]) {
+                continue;
+            }
+
+            let mut attempt = bound.clone();
+            if self.bind(term, cells[i], &mut attempt) {
+                used[i] = true;
+                if self.permuted(rest, cells, used, &mut attempt) {
+                    *bound = attempt;
+                    return true;
+                }
+                used[i] = false;
+            }
+        }
+
+        false
+    }
+
+    fn bind(&self, term: Term, state: u8, bound: &mut [Option<u8>]) -> bool {
+        match term {
+            Term::State(expected) => expected == state,
+            Term::Var(var) => match bound[var] {
+                Some(value) => value == state,
+                None if self.vars[var][state as usize] => {
+                    bound[var] = Some(state);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// Parses the text of a Golly `.rule` file. Errors name the line they occur on.
+impl FromStr for RuleTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut table = Parser::default().parse(s)?;
+        let mut hasher = DefaultHasher::new();
+        ("table", s).hash(&mut hasher);
+        table.fingerprint = hasher.finish();
+        Ok(table)
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    name: Option<String>,
+    n_states: Option<usize>,
+    neighbourhood: Option<Neighbourhood>,
+    symmetries: Option<Symmetries>,
+    var_names: HashMap<String, usize>,
+    vars: Vec<Vec<bool>>,
+    transitions: Vec<Transition>,
+    colors: HashMap<usize, [f32; 3]>,
+    has_table: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    Table,
+    Colors,
+    Other,
+}
+
+impl Parser {
+    fn parse(mut self, text: &str) -> Result<RuleTable, String> {
+        let mut section = Section::Header;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| format!("line {}: {message}", i + 1);
+            if let Some(header) = line.strip_prefix('@') {
+                let mut words = header.split_whitespace();
+                section = match words.next().unwrap_or_default() {
+                    "RULE" => {
+                        self.name = words.next().map(str::to_string);
+                        Section::Header
+                    }
+                    "TABLE" => {
+                        self.has_table = true;
+                        Section::Table
+                    }
+                    "COLORS" => Section::Colors,
+                    _ => Section::Other,
+                };
+                continue;
+            }
+
+            match section {
+                Section::Table => self.table_line(line).map_err(error)?,
+                Section::Colors => self.colors_line(line).map_err(error)?,
+                Section::Header | Section::Other => {}
+            }
+        }
+
+        self.finish()
+    }
+
+    fn table_line(&mut self, line: &str) -> Result<(), String> {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "n_states" => {
+                    let n: usize = value
+                        .parse()
+                        .map_err(|_| format!("`{value}` is not a number of states"))?;
+                    if !(2..=MAX_STATES).contains(&n) {
+                        return Err(format!("n_states must be within 2..={MAX_STATES}, not {n}"));
+                    }
+                    self.n_states = Some(n);
+                }
+                "neighborhood" => {
+                    self.neighbourhood = Some(match value {
+                        "Moore" => Neighbourhood::Moore,
+                        "vonNeumann" => Neighbourhood::VonNeumann,
+                        "hexagonal" | "oneDimensional" => {
+                            return Err(format!("the {value} neighbourhood is not supported"))
+                        }
+                        _ => return Err(format!("unknown neighbourhood `{value}`")),
+                    })
+                }
+                "symmetries" => {
+                    self.symmetries = Some(match value {
+                        "none" => Symmetries::None,
+                        "rotate4" => Symmetries::Rotate4,
+                        "rotate8" => Symmetries::Rotate8,
+                        "reflect_horizontal" => Symmetries::ReflectHorizontal,
+                        "rotate4reflect" => Symmetries::Rotate4Reflect,
+                        "rotate8reflect" => Symmetries::Rotate8Reflect,
+                        "permute" => Symmetries::Permute,
+                        _ => return Err(format!("unknown symmetries `{value}`")),
+                    })
+                }
+                key => return Err(format!("unknown setting `{key}`")),
+            }
+            return Ok(());
+        }
+
+        if let Some(declaration) = line.strip_prefix("var ") {
+            return self.var_line(declaration);
+        }
+
+        self.transition_line(line)
+    }
+
+    fn n_states(&self) -> Result<usize, String> {
+        self.n_states
+            .ok_or_else(|| "n_states must be set before variables and transitions".to_string())
+    }
+
+    fn var_line(&mut self, declaration: &str) -> Result<(), String> {
+        let n_states = self.n_states()?;
+        let (name, set) = declaration
+            .split_once('=')
+            .ok_or_else(|| format!("expected `var name={{...}}`, found `var {declaration}`"))?;
+        let name = name.trim();
+        let set = set
+            .trim()
+            .strip_prefix('{')
+            .and_then(|set| set.strip_suffix('}'))
+            .ok_or_else(|| format!("the states of `{name}` must be enclosed in braces"))?;
+
+        let mut states = vec![false; n_states];
+        for item in set.split(',').map(str::trim) {
+            match self.term(item)? {
+                Term::State(state) => states[state as usize] = true,
+                Term::Var(var) => {
+                    for (state, &included) in self.vars[var].iter().enumerate() {
+                        states[state] |= included;
+                    }
+                }
+            }
+        }
+
+        self.var_names.insert(name.to_string(), self.vars.len());
+        self.vars.push(states);
+        Ok(())
+    }
+
+    fn transition_line(&mut self, line: &str) -> Result<(), String> {
+        let neighbourhood = self
+            .neighbourhood
+            .ok_or_else(|| "neighborhood must be set before transitions".to_string())?;
+        if self.symmetries.is_none() {
+            return Err("symmetries must be set before transitions".to_string());
+        }
+
+        // Without commas every character is a term, as Golly allows for up to 10 states.
+        let items: Vec<String> = if line.contains(',') {
+            line.split(',')
+                .map(|item| item.trim().to_string())
+                .collect()
+        } else {
+            line.chars()
+                .filter(|c| !c.is_whitespace())
+                .map(String::from)
+                .collect()
+        };
+
+        let expected = neighbourhood.offsets().len() + 2;
+        if items.len() != expected {
+            return Err(format!(
+                "a transition needs {expected} terms (centre, neighbours, new state), found {}",
+                items.len()
+            ));
+        }
+
+        let terms = items
+            .iter()
+            .map(|item| self.term(item))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (&output, inputs) = terms.split_last().unwrap_or((&Term::State(0), &[]));
+        if let Term::Var(_) = output {
+            if !inputs.contains(&output) {
+                let name = items.last().map_or("", String::as_str);
+                return Err(format!(
+                    "the new state `{name}` is a variable not bound by the inputs"
+                ));
+            }
+        }
+
+        self.transitions.push(Transition {
+            inputs: inputs.to_vec(),
+            output,
+        });
+        Ok(())
+    }
+
+    fn term(&self, item: &str) -> Result<Term, String> {
+        let n_states = self.n_states()?;
+        if let Some(&var) = self.var_names.get(item) {
+            return Ok(Term::Var(var));
+        }
+
+        match item.parse::<usize>() {
+            Ok(state) if state < n_states => Ok(Term::State(state as u8)),
+            Ok(state) => Err(format!(
+                "state {state} is out of range; n_states is {n_states}"
+            )),
+            Err(_) => Err(format!(
+                "`{item}` is neither a state nor a declared variable"
+            )),
+        }
+    }
+
+    fn colors_line(&mut self, line: &str) -> Result<(), String> {
+        let numbers = line
+            .split_whitespace()
+            .map(|word| word.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("expected `state red green blue`, found `{line}`"))?;
+        let [state, r, g, b] = numbers[..] else {
+            return Err(format!("expected `state red green blue`, found `{line}`"));
+        };
+        if [r, g, b].iter().any(|&channel| channel > 255) {
+            return Err(format!(
+                "colour channels must be within 0..=255 in `{line}`"
+            ));
+        }
+
+        self.colors.insert(
+            state as usize,
+            [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0],
+        );
+        Ok(())
+    }
+
+    fn finish(self) -> Result<RuleTable, String> {
+        if !self.has_table {
+            return Err("the file has no @TABLE section".to_string());
+        }
+        let n_states = self.n_states()?;
+        let neighbourhood = self.neighbourhood.unwrap_or(Neighbourhood::Moore);
+        let symmetries = self.symmetries.unwrap_or(Symmetries::None);
+
+        let colors = (0..n_states)
+            .map(|state| {
+                self.colors
+                    .get(&state)
+                    .copied()
+                    .unwrap_or_else(|| default_color(state, n_states))
+            })
+            .collect();
+
+        Ok(RuleTable {
+            name: self.name.unwrap_or_else(|| "Unnamed".to_string()),
+            n_states,
+            neighbourhood,
+            symmetries,
+            colors,
+            vars: self.vars,
+            transitions: self.transitions,
+            orders: orders(neighbourhood, symmetries)?,
+            cache: HashMap::new(),
+            fingerprint: 0,
+        })
+    }
+}
+
+/// Orders of reading the neighbours for every symmetry of the transitions.
+fn orders(neighbourhood: Neighbourhood, symmetries: Symmetries) -> Result<Vec<Vec<usize>>, String> {
+    let n = neighbourhood.offsets().len();
+    // Steps between neighbours for a quarter and an eighth of a turn.
+    let (quarter, eighth) = match neighbourhood {
+        Neighbourhood::Moore => (2, Some(1)),
+        Neighbourhood::VonNeumann => (1, None),
+    };
+
+    let (step, reflect) = match symmetries {
+        Symmetries::None | Symmetries::Permute => return Ok(vec![(0..n).collect()]),
+        Symmetries::ReflectHorizontal => (n, true),
+        Symmetries::Rotate4 => (quarter, false),
+        Symmetries::Rotate4Reflect => (quarter, true),
+        Symmetries::Rotate8 | Symmetries::Rotate8Reflect => (
+            eighth.ok_or("rotate8 symmetries need the Moore neighbourhood")?,
+            symmetries == Symmetries::Rotate8Reflect,
+        ),
+    };
+
+    let mut orders = vec![];
+    for rotation in (0..n).step_by(step) {
+        orders.push((0..n).map(|i| (i + rotation) % n).collect());
+        if reflect {
+            // Mirror left and right: N stays, E and W swap.
+            orders.push((0..n).map(|i| ((n - i) % n + rotation) % n).collect());
+        }
+    }
+    Ok(orders)
+}
+
+/// Colour of a state without one in `@COLORS`: a ramp from blue to red.
+fn default_color(state: usize, n_states: usize) -> [f32; 3] {
+    let t = state as f32 / (n_states - 1).max(1) as f32;
+    [t, 0.3 + 0.4 * (1.0 - (2.0 * t - 1.0).abs()), 1.0 - t]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// Cells of the grid as positions and states.
+    type States = BTreeSet<((usize, usize), u8)>;
+
+    /// Runs the table for some generations from the cells, offset into the grid.
+    fn run(table: &mut RuleTable, cells: &[((usize, usize), u8)], generations: usize) -> States {
+        let mut grid = Grid::new();
+        for &((x, y), state) in cells {
+            let cell = Cell {
+                state,
+                ..Cell::default()
+            };
+            grid.spawn_cell(Vector2::new(50 + x, 50 + y), cell);
+        }
+
+        let mut chunks = ActiveChunks::default();
+        for _ in 0..generations {
+            table.step(&mut grid, 1, &mut chunks);
+        }
+
+        grid.cell_positions()
+            .into_iter()
+            .filter_map(|pos| {
+                let state = grid.cells.get(pos)?.state;
+                Some(((pos.x - 50, pos.y - 50), state))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wireworld_clock_cycles() {
+        let mut table: RuleTable = PRESETS[0].1.parse().unwrap();
+        // A loop of ten conductors with the corners cut, so that every conductor
+        // touches exactly two others.
+        let ring = [
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (4, 1),
+            (4, 2),
+            (3, 3),
+            (2, 3),
+            (1, 3),
+            (0, 2),
+            (0, 1),
+        ];
+        let clock = |head: usize| -> Vec<_> {
+            ring.iter()
+                .enumerate()
+                .map(|(i, &pos)| {
+                    let state = match (i + ring.len() - head) % ring.len() {
+                        0 => 1,
+                        9 => 2,
+                        _ => 3,
+                    };
+                    (pos, state)
+                })
+                .collect()
+        };
+
+        let start = clock(1);
+        assert_eq!(run(&mut table, &start, 1), run(&mut table, &clock(2), 0));
+        assert_eq!(run(&mut table, &start, 7), run(&mut table, &clock(8), 0));
+        assert_eq!(run(&mut table, &start, 10), run(&mut table, &start, 0));
+    }
+
+    #[test]
+    fn running_leaves_tables_equal() {
+        let fresh: RuleTable = PRESETS[0].1.parse().unwrap();
+        let mut used = fresh.clone();
+        run(&mut used, &[((0, 0), 3), ((1, 0), 1), ((2, 0), 3)], 3);
+        assert!(!used.cache.is_empty());
+        assert_eq!(used, fresh);
+        assert_ne!(used, PRESETS[1].1.parse().unwrap());
+    }
+
+    #[test]
+    fn cache_stays_bounded() {
+        let mut table: RuleTable = PRESETS[1].1.parse().unwrap();
+        let keys = |start: usize, count: usize| {
+            (start..start + count)
+                .map(|i| (i.to_le_bytes().to_vec(), 0))
+                .collect::<HashMap<_, _>>()
+        };
+
+        table.remember(keys(0, CACHE_LIMIT - 10));
+        assert_eq!(table.cache.len(), CACHE_LIMIT - 10);
+        table.remember(keys(CACHE_LIMIT, 20));
+        assert_eq!(table.cache.len(), 20);
+    }
+
+    #[test]
+    fn brians_brain_glider_moves() {
+        let mut table: RuleTable = PRESETS[1].1.parse().unwrap();
+        let glider = |y: usize| [((0, y), 2), ((1, y), 2), ((0, y + 1), 1), ((1, y + 1), 1)];
+
+        assert_eq!(
+            run(&mut table, &glider(0), 5),
+            run(&mut table, &glider(5), 0)
+        );
+    }
+
+    /// Table of two states whose transitions turn an empty centre into a cell.
+    fn table(neighbourhood: &str, symmetries: &str, transitions: &[&str]) -> RuleTable {
+        let mut text =
+            format!("@TABLE\nn_states:2\nneighborhood:{neighbourhood}\nsymmetries:{symmetries}\n");
+        for transition in transitions {
+            text += &format!("0{transition}1\n");
+        }
+        text.parse().unwrap()
+    }
+
+    /// Whether the tables agree on every neighbourhood of an empty centre.
+    fn agree(a: &RuleTable, b: &RuleTable) -> bool {
+        let n = a.neighbourhood.offsets().len();
+        (0..1 << n).all(|bits: usize| {
+            let key: Vec<u8> = std::iter::once(0)
+                .chain((0..n).map(|i| (bits >> i & 1) as u8))
+                .collect();
+            a.next_state(&mut key.clone(), &mut HashMap::new())
+                == b.next_state(&mut key.clone(), &mut HashMap::new())
+        })
+    }
+
+    #[test]
+    fn symmetries_match_hand_expanded_tables() {
+        // N, NE and SE, and its mirror image N, NW and SW, rotated by eighths of a turn.
+        let turns = [
+            "11010000", "01101000", "00110100", "00011010", "00001101", "10000110", "01000011",
+            "10100001",
+        ];
+        let mirrored = [
+            "10000101", "11000010", "01100001", "10110000", "01011000", "00101100", "00010110",
+            "00001011",
+        ];
+        let quarters = |patterns: [&'static str; 8]| patterns.into_iter().step_by(2);
+
+        let cases: [(&str, Vec<&str>); 6] = [
+            ("none", vec![turns[0]]),
+            ("reflect_horizontal", vec![turns[0], mirrored[0]]),
+            ("rotate4", quarters(turns).collect()),
+            (
+                "rotate4reflect",
+                quarters(turns).chain(quarters(mirrored)).collect(),
+            ),
+            ("rotate8", turns.to_vec()),
+            ("rotate8reflect", [turns, mirrored].concat()),
+        ];
+        for (symmetries, expanded) in cases {
+            assert!(
+                agree(
+                    &table("Moore", symmetries, &[turns[0]]),
+                    &table("Moore", "none", &expanded)
+                ),
+                "{symmetries}"
+            );
+        }
+
+        let permuted = table("Moore", "permute", &[turns[0]]);
+        let three: Vec<String> = (0..1 << 8_usize)
+            .filter(|bits: &usize| bits.count_ones() == 3)
+            .map(|bits| {
+                (0..8)
+                    .map(|i| if bits >> i & 1 == 1 { '1' } else { '0' })
+                    .collect()
+            })
+            .collect();
+        let three: Vec<&str> = three.iter().map(String::as_str).collect();
+        assert!(agree(&permuted, &table("Moore", "none", &three)));
+
+        // N and E.
+        assert!(agree(
+            &table("vonNeumann", "rotate4", &["1100"]),
+            &table("vonNeumann", "none", &["1100", "0110", "0011", "1001"])
+        ));
+    }
+
+    #[test]
+    fn errors_name_their_line() {
+        let header = "@RULE Broken\n\n@TABLE\n# a comment\nn_states:3\nneighborhood:Moore\nsymmetries:none\n";
+        let cases = [
+            (
+                "0,1,2\n",
+                "line 8: a transition needs 10 terms (centre, neighbours, new state), found 3",
+            ),
+            (
+                "var a=0,1\n",
+                "line 8: the states of `a` must be enclosed in braces",
+            ),
+            (
+                "\n0,0,0,0,0,0,0,0,0,3\n",
+                "line 9: state 3 is out of range; n_states is 3",
+            ),
+            (
+                "0,0,0,0,0,0,0,0,0,x\n",
+                "line 8: `x` is neither a state nor a declared variable",
+            ),
+            (
+                "var a={1,2}\n0,0,0,0,0,0,0,0,0,a\n",
+                "line 9: the new state `a` is a variable not bound by the inputs",
+            ),
+            ("size:4\n", "line 8: unknown setting `size`"),
+            (
+                "@COLORS\n1 255 0\n",
+                "line 9: expected `state red green blue`, found `1 255 0`",
+            ),
+            (
+                "@COLORS\n1 256 0 0\n",
+                "line 9: colour channels must be within 0..=255 in `1 256 0 0`",
+            ),
+        ];
+        for (body, message) in cases {
+            assert_eq!(
+                format!("{header}{body}").parse::<RuleTable>(),
+                Err(message.to_string())
+            );
+        }
+
+        let errors = [
+            ("@RULE Empty\n", "the file has no @TABLE section"),
+            (
+                "@TABLE\nn_states:300\n",
+                "line 2: n_states must be within 2..=256, not 300",
+            ),
+            (
+                "@TABLE\nvar a={0,1}\n",
+                "line 2: n_states must be set before variables and transitions",
+            ),
+            (
+                "@TABLE\nn_states:2\nneighborhood:hexagonal\n",
+                "line 3: the hexagonal neighbourhood is not supported",
+            ),
+            (
+                "@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:rotate8\n",
+                "rotate8 symmetries need the Moore neighbourhood",
+            ),
+        ];
+        for (text, message) in errors {
+            assert_eq!(text.parse::<RuleTable>(), Err(message.to_string()));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use nalgebra::Vector2;
+
+use crate::{
+    grid::{store::CellRef, Grid},
+    simulation::{Rng, Settings},
+    zone::ZoneKind,
+};
+
+use super::{
+    brain::{output, Brain, OUTPUTS},
+    sensors::{choose, NeighbourView},
+    CellType, Observation, Response,
+};
+
+/// What a cell wants to do this tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Idle,
+    /// Divide, placing the daughter into the given free slot.
+    Divide(Vector2<usize>),
+    /// Mate with the cell in the first slot, placing the offspring into the second,
+    /// free one.
+    Mate(Vector2<usize>, Vector2<usize>),
+    /// Try to eat the cell in the given slot.
+    Attack(Vector2<usize>),
+    /// Feed on the dead matter in the given slot, which may be the cell's own.
+    Scavenge(Vector2<usize>),
+    /// Move into the given free slot.
+    Move(Vector2<usize>),
+    /// Bond with the cell in the given slot.
+    Bond(Vector2<usize>),
+    /// Break the bond with the cell in the given slot.
+    Unbond(Vector2<usize>),
+    /// Turn into the given type.
+    Differentiate(CellType),
+}
+
+impl Action {
+    /// Slot the action is aimed at.
+    pub fn target(&self) -> Option<Vector2<usize>> {
+        match *self {
+            Action::Idle | Action::Differentiate(_) => None,
+            Action::Divide(target)
+            | Action::Mate(_, target)
+            | Action::Attack(target)
+            | Action::Scavenge(target)
+            | Action::Move(target)
+            | Action::Bond(target)
+            | Action::Unbond(target) => Some(target),
+        }
+    }
+}
+
+impl CellRef<'_> {
+    /// Chooses the action of the cell standing in `pos` without changing the grid,
+    /// with its brain if it has one and with the rules of its genome otherwise.
+    pub fn decide(
+        &self,
+        pos: Vector2<usize>,
+        grid: &Grid,
+        settings: &Settings,
+        rng: &mut Rng,
+    ) -> Action {
+        let observation = self.observe(pos, grid);
+        match self.brain {
+            Some(brain) => self.decide_by_brain(brain, &observation, settings, rng),
+            None => self.decide_by_rules(&observation, settings, rng),
+        }
+    }
+
+    /// Energy the cell needs to divide.
+    pub fn division_energy(&self, settings: &Settings) -> f32 {
+        settings.division_energy * self.cell_type.physiology().division_energy
+    }
+
+    fn decide_by_brain(
+        &self,
+        brain: &Brain,
+        observation: &Observation,
+        settings: &Settings,
+        rng: &mut Rng,
+    ) -> Action {
+        let free = observation.free().map(|n| n.pos);
+        let prey = observation.unbonded().map(|n| n.pos);
+        let partners = Self::bond_partners(observation, settings);
+        let remains = Self::richest_remains(observation);
+        let can_divide = self.energy >= self.division_energy(settings);
+
+        let thought = brain.think(Brain::sense(self, observation, settings));
+        let possible = |o: usize| match o {
+            output::DIVIDE => can_divide && free.clone().next().is_some(),
+            output::ATTACK => prey.clone().next().is_some(),
+            output::SCAVENGE => remains.is_some(),
+            output::MOVE => free.clone().next().is_some(),
+            output::BOND => partners.clone().next().is_some(),
+            _ => true,
+        };
+        let chosen = (0..OUTPUTS)
+            .filter(|&o| possible(o))
+            .max_by(|&a, &b| thought.outputs[a].total_cmp(&thought.outputs[b]));
+
+        let action = match chosen {
+            Some(output::DIVIDE) => choose(free, rng).map(Action::Divide),
+            Some(output::ATTACK) => choose(prey, rng).map(Action::Attack),
+            Some(output::SCAVENGE) => remains.map(Action::Scavenge),
+            Some(output::MOVE) => {
+                Self::best(observation.free(), |n| n.levels.nutrients, rng).map(Action::Move)
+            }
+            Some(output::BOND) => choose(partners, rng).map(Action::Bond),
+            _ => None,
+        };
+        action.unwrap_or(Action::Idle)
+    }
+
+    fn decide_by_rules(
+        &self,
+        observation: &Observation,
+        settings: &Settings,
+        rng: &mut Rng,
+    ) -> Action {
+        // Responses triggered by the signal chemicals in the slot, with their channel.
+        let signals = observation.levels.signals;
+        let responses = (0..signals.len())
+            .filter(|&channel| self.genome.receptors[channel].is_triggered(signals[channel]))
+            .map(|channel| (channel, self.genome.receptors[channel].response));
+
+        for (_, response) in responses.clone() {
+            if let Response::Differentiate(cell_type) = response {
+                if cell_type != self.cell_type {
+                    return Action::Differentiate(cell_type);
+                }
+            }
+        }
+
+        let free = observation.free().map(|n| n.pos);
+        let has_free = free.clone().next().is_some();
+        if rng.next_f32() < self.genome.mating && self.energy >= settings.mating_energy && has_free
+        {
+            let partners = observation
+                .occupied()
+                .filter(|&(_, other)| {
+                    self.is_compatible(&other, settings.mating_kinship, settings.mating_distance)
+                })
+                .map(|(n, _)| n.pos);
+            if let Some(partner) = choose(partners, rng) {
+                if let Some(target) = choose(free.clone(), rng) {
+                    return Action::Mate(partner, target);
+                }
+            }
+        }
+
+        let inhibited = responses
+            .clone()
+            .any(|(_, response)| response == Response::InhibitDivision);
+        if self.energy >= self.division_energy(settings) && !inhibited {
+            if let Some(target) = choose(free.clone(), rng) {
+                return Action::Divide(target);
+            }
+        }
+
+        if rng.next_f32() < self.genome.aggression {
+            if let Some(target) = choose(observation.unbonded().map(|n| n.pos), rng) {
+                return Action::Attack(target);
+            }
+        }
+
+        if rng.next_f32() < self.genome.scavenging {
+            if let Some(target) = Self::richest_remains(observation) {
+                return Action::Scavenge(target);
+            }
+        }
+
+        if rng.next_f32() < self.genome.motility && has_free {
+            // Follow the first sensed signal gradient, or else forage up the gradients
+            // of the resources the cell can feed on; toxic zones are avoided.
+            let signal = responses
+                .clone()
+                .find(|&(_, response)| matches!(response, Response::Attract | Response::Repel));
+            let forage = self.forage_direction(observation);
+            let score = |n: &NeighbourView| match signal {
+                Some((channel, Response::Attract)) => n.levels.signals[channel],
+                Some((channel, _)) => -n.levels.signals[channel],
+                None => forage.dot(&direction(observation.pos, n.pos)),
+            };
+            let slots = observation
+                .free()
+                .filter(|n| n.zone != Some(ZoneKind::Toxic));
+            if let Some(target) = Self::best(slots, score, rng) {
+                return Action::Move(target);
+            }
+        }
+
+        if rng.next_f32() < self.genome.adhesion {
+            if let Some(target) = choose(Self::bond_partners(observation, settings), rng) {
+                return Action::Bond(target);
+            }
+        }
+
+        if rng.next_f32() < (1.0 - self.genome.adhesion) * settings.bond_break_chance {
+            let bonded = observation.neighbours().filter(|n| n.bonded).map(|n| n.pos);
+            if let Some(target) = choose(bonded, rng) {
+                return Action::Unbond(target);
+            }
+        }
+
+        Action::Idle
+    }
+
+    /// Sum of the resource gradients weighted by how well the cell feeds on each.
+    fn forage_direction(&self, observation: &Observation) -> Vector2<f32> {
+        let enzymes = &self.genome.enzymes;
+        let gradients = &observation.gradients;
+        gradients.light * enzymes.photosynthesis
+            + gradients.nutrients * enzymes.heterotrophy
+            + gradients.minerals * enzymes.chemosynthesis
+    }
+
+    /// Unbonded kin neighbours the cell may bond with.
+    fn bond_partners<'b>(
+        observation: &'b Observation,
+        settings: &'b Settings,
+    ) -> impl Iterator<Item = Vector2<usize>> + Clone + 'b {
+        observation
+            .unbonded()
+            .filter(|n| n.kinship >= settings.bond_kinship)
+            .map(|n| n.pos)
+    }
+
+    /// Slot with the most dead matter among the neighbours and the cell's own.
+    fn richest_remains(observation: &Observation) -> Option<Vector2<usize>> {
+        observation
+            .neighbours()
+            .map(|n| (n.pos, n.levels.remains))
+            .chain([(observation.pos, observation.levels.remains)])
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|&(_, remains)| remains > 0.0)
+            .map(|(pos, _)| pos)
+    }
+
+    /// Slot of the view with the highest score, picking randomly among equally
+    /// good ones.
+    fn best<'b, 'a: 'b>(
+        views: impl Iterator<Item = &'b NeighbourView<'a>> + Clone,
+        score: impl Fn(&NeighbourView) -> f32,
+        rng: &mut Rng,
+    ) -> Option<Vector2<usize>> {
+        let count = views.clone().count();
+        if count == 0 {
+            return None;
+        }
+
+        let offset = rng.next_index(count);
+        views
+            .cycle()
+            .skip(offset)
+            .take(count)
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .map(|n| n.pos)
+    }
+}
+
+/// Unit step from `from` to its neighbour `to`.
+fn direction(from: Vector2<usize>, to: Vector2<usize>) -> Vector2<f32> {
+    Vector2::new(to.x as f32 - from.x as f32, to.y as f32 - from.y as f32)
+}
@@ -0,0 +1,248 @@
+use crate::{
+    grid::store::CellRef,
+    simulation::{Rng, Settings},
+};
+
+use super::Observation;
+
+pub const INPUTS: usize = 9;
+pub const HIDDEN: usize = 8;
+pub const OUTPUTS: usize = 6;
+
+pub const INPUT_NAMES: [&str; INPUTS] = [
+    "Energy",
+    "Light",
+    "Nutrients",
+    "Minerals",
+    "Remains",
+    "Free slots",
+    "Neighbours",
+    "Kinship",
+    "Bias",
+];
+
+pub const OUTPUT_NAMES: [&str; OUTPUTS] = ["Idle", "Divide", "Attack", "Scavenge", "Move", "Bond"];
+
+/// Index of every output in [`OUTPUT_NAMES`].
+pub mod output {
+    pub const IDLE: usize = 0;
+    pub const DIVIDE: usize = 1;
+    pub const ATTACK: usize = 2;
+    pub const SCAVENGE: usize = 3;
+    pub const MOVE: usize = 4;
+    pub const BOND: usize = 5;
+}
+
+/// Weights of a small feed-forward network choosing the actions of a cell.
+///
+/// The sensed inputs feed one `tanh` hidden layer; the output with the highest
+/// activation among the possible actions wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Brain {
+    /// Input to hidden weights, row by row for every hidden neuron.
+    hidden: Vec<f32>,
+    /// Hidden to output weights, row by row for every output, each ending in a bias.
+    output: Vec<f32>,
+}
+
+/// Activations of every layer for one decision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thought {
+    pub inputs: [f32; INPUTS],
+    pub hidden: [f32; HIDDEN],
+    pub outputs: [f32; OUTPUTS],
+}
+
+impl Brain {
+    pub fn hidden_weight(&self, hidden: usize, input: usize) -> f32 {
+        self.hidden[hidden * INPUTS + input]
+    }
+
+    pub fn output_weight(&self, output: usize, hidden: usize) -> f32 {
+        self.output[output * (HIDDEN + 1) + hidden]
+    }
+
+    /// Inputs of the network for the cell, each roughly in `0.0..=1.0`.
+    pub fn sense(cell: &CellRef, observation: &Observation, settings: &Settings) -> [f32; INPUTS] {
+        let levels = &observation.levels;
+        [
+            cell.energy / cell.division_energy(settings),
+            levels.light,
+            levels.nutrients / settings.nutrient_capacity,
+            levels.minerals / settings.mineral_capacity,
+            levels.remains / settings.max_mass,
+            observation.free().count() as f32 / 4.0,
+            observation.occupied().count() as f32 / 4.0,
+            observation.mean_kinship(),
+            1.0,
+        ]
+    }
+
+    pub fn think(&self, inputs: [f32; INPUTS]) -> Thought {
+        let hidden = std::array::from_fn(|h| {
+            (0..INPUTS)
+                .map(|i| self.hidden_weight(h, i) * inputs[i])
+                .sum::<f32>()
+                .tanh()
+        });
+        let outputs = std::array::from_fn(|o| {
+            (0..HIDDEN)
+                .map(|h| self.output_weight(o, h) * hidden[h])
+                .sum::<f32>()
+                + self.output_weight(o, HIDDEN)
+        });
+
+        Thought {
+            inputs,
+            hidden,
+            outputs,
+        }
+    }
+
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        for weight in self.hidden.iter_mut().chain(self.output.iter_mut()) {
+            *weight += rng.next_signed() * strength;
+        }
+    }
+
+    /// Takes every weight from either parent with equal chance.
+    pub fn crossover(&self, other: &Brain, rng: &mut Rng) -> Brain {
+        let mut pick = |a: &[f32], b: &[f32]| -> Vec<f32> {
+            a.iter()
+                .zip(b)
+                .map(|(&a, &b)| if rng.next_f32() < 0.5 { a } else { b })
+                .collect()
+        };
+
+        Brain {
+            hidden: pick(&self.hidden, &other.hidden),
+            output: pick(&self.output, &other.output),
+        }
+    }
+}
+
+/// Blank network that divides whenever it can and idles otherwise; mutations
+/// shape it from there.
+impl Default for Brain {
+    fn default() -> Self {
+        let mut output = vec![0.0; OUTPUTS * (HIDDEN + 1)];
+        output[output::IDLE * (HIDDEN + 1) + HIDDEN] = 0.1;
+        output[output::DIVIDE * (HIDDEN + 1) + HIDDEN] = 0.5;
+
+        Self {
+            hidden: vec![0.0; HIDDEN * INPUTS],
+            output,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+    use crate::{
+        cell::{Action, Cell},
+        grid::Grid,
+        zone::{Zone, ZoneKind},
+    };
+
+    /// Network ignoring its inputs and ranking the outputs by the given biases.
+    fn ranked(biases: [f32; OUTPUTS]) -> Brain {
+        let mut brain = Brain::default();
+        for (o, bias) in biases.into_iter().enumerate() {
+            brain.output[o * (HIDDEN + 1) + HIDDEN] = bias;
+        }
+
+        brain
+    }
+
+    #[test]
+    fn thinks_with_the_weights() {
+        let mut brain = ranked([0.5, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        // The first hidden neuron reads the energy, the second the bias input.
+        brain.hidden[0] = 1.0;
+        brain.hidden[INPUTS + INPUTS - 1] = -2.0;
+        brain.output[output::ATTACK * (HIDDEN + 1)] = 2.0;
+        brain.output[output::MOVE * (HIDDEN + 1) + 1] = 1.0;
+
+        let mut inputs = [0.0; INPUTS];
+        inputs[0] = 1.0;
+        inputs[INPUTS - 1] = 1.0;
+        let thought = brain.think(inputs);
+
+        assert_eq!(thought.hidden[0], 1.0f32.tanh());
+        assert_eq!(thought.hidden[1], (-2.0f32).tanh());
+        assert!(thought.hidden[2..].iter().all(|&h| h == 0.0));
+        assert_eq!(
+            thought.outputs,
+            [0.5, 0.0, 2.0 * 1.0f32.tanh(), 0.0, (-2.0f32).tanh(), 0.0]
+        );
+    }
+
+    #[test]
+    fn picks_the_strongest_possible_action() {
+        let brain = ranked([0.1, 1.0, 3.0, 2.5, 1.5, 2.0]);
+        let settings = Settings::default();
+        let pos = Vector2::new(5, 5);
+        let decide = |grid: &Grid, seed| {
+            let cell = grid.cells.get(pos).unwrap();
+            cell.decide(pos, grid, &settings, &mut Rng::new(seed))
+        };
+
+        // Alone, without remains or the energy to divide, moving is all that is left.
+        let mut grid = Grid::new();
+        grid.spawn_cell(
+            pos,
+            Cell {
+                brain: Some(brain),
+                ..Cell::default()
+            },
+        );
+        for seed in 0..10 {
+            assert!(matches!(decide(&grid, seed), Action::Move(_)));
+        }
+
+        // A stranger next to it is the strongest choice.
+        let stranger = Vector2::new(6, 5);
+        let color = [0.0, 1.0, 0.0];
+        grid.spawn_cell(
+            stranger,
+            Cell {
+                color,
+                ..Cell::default()
+            },
+        );
+        for seed in 0..10 {
+            assert_eq!(decide(&grid, seed), Action::Attack(stranger));
+        }
+
+        // Walled in, it can only idle.
+        grid.remove_cell(stranger);
+        for (x, y) in [(4, 5), (6, 5), (5, 4), (5, 6)] {
+            grid.set_zone(Vector2::new(x, y), Some(Zone::new(ZoneKind::Wall)));
+        }
+        for seed in 0..10 {
+            assert_eq!(decide(&grid, seed), Action::Idle);
+        }
+    }
+
+    #[test]
+    fn division_mutates_the_brain() {
+        let mut cell = Cell {
+            brain: Some(Brain::default()),
+            ..Cell::default()
+        };
+        cell.mutate(0.1, &mut Rng::new(7));
+
+        let (parent, child) = (Brain::default(), cell.brain.unwrap());
+        let weights = |brain: &Brain| [brain.hidden.clone(), brain.output.clone()].concat();
+        let changes: Vec<f32> = weights(&parent)
+            .iter()
+            .zip(&weights(&child))
+            .map(|(a, b)| (a - b).abs())
+            .collect();
+        assert!(changes.iter().any(|&change| change > 0.0));
+        assert!(changes.iter().all(|&change| change <= 0.1));
+    }
+}
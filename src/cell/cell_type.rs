@@ -0,0 +1,118 @@
+/// Specialisation of a cell.
+///
+/// The discriminant is passed to `cell.frag` to pick the look of the cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellType {
+    /// Unspecialised; does a bit of everything.
+    #[default]
+    Stem = 0,
+    /// Feeds on light.
+    Leaf = 1,
+    /// Absorbs nutrients.
+    Root = 2,
+    /// Moves cheaply.
+    Mover = 3,
+    /// Hard to eat.
+    Defender = 4,
+    /// Divides early.
+    Reproductive = 5,
+}
+
+/// Multipliers a cell type applies to the intake and costs of a cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Physiology {
+    pub light_intake: f32,
+    pub nutrient_intake: f32,
+    pub mineral_intake: f32,
+    /// Multiplies the basal cost.
+    pub upkeep: f32,
+    pub move_cost: f32,
+    /// Multiplies the mass of the cell when it is attacked.
+    pub defence: f32,
+    /// Multiplies the energy the cell needs to divide.
+    pub division_energy: f32,
+}
+
+impl Default for Physiology {
+    fn default() -> Self {
+        Self {
+            light_intake: 1.0,
+            nutrient_intake: 1.0,
+            mineral_intake: 1.0,
+            upkeep: 1.0,
+            move_cost: 1.0,
+            defence: 1.0,
+            division_energy: 1.0,
+        }
+    }
+}
+
+impl CellType {
+    pub const COUNT: usize = 6;
+
+    pub const ALL: [CellType; Self::COUNT] = [
+        CellType::Stem,
+        CellType::Leaf,
+        CellType::Root,
+        CellType::Mover,
+        CellType::Defender,
+        CellType::Reproductive,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CellType::Stem => "Stem",
+            CellType::Leaf => "Leaf",
+            CellType::Root => "Root",
+            CellType::Mover => "Mover",
+            CellType::Defender => "Defender",
+            CellType::Reproductive => "Reproductive",
+        }
+    }
+
+    pub fn physiology(self) -> Physiology {
+        let default = Physiology::default();
+        match self {
+            CellType::Stem => default,
+            CellType::Leaf => Physiology {
+                light_intake: 2.0,
+                nutrient_intake: 0.25,
+                move_cost: 2.0,
+                division_energy: 2.0,
+                ..default
+            },
+            CellType::Root => Physiology {
+                light_intake: 0.25,
+                nutrient_intake: 2.5,
+                mineral_intake: 2.5,
+                move_cost: 4.0,
+                division_energy: 2.0,
+                ..default
+            },
+            CellType::Mover => Physiology {
+                light_intake: 0.5,
+                nutrient_intake: 0.5,
+                upkeep: 1.2,
+                move_cost: 0.25,
+                division_energy: 1.5,
+                ..default
+            },
+            CellType::Defender => Physiology {
+                light_intake: 0.5,
+                nutrient_intake: 0.5,
+                upkeep: 1.5,
+                move_cost: 2.0,
+                defence: 3.0,
+                division_energy: 2.0,
+                ..default
+            },
+            CellType::Reproductive => Physiology {
+                light_intake: 0.5,
+                nutrient_intake: 0.5,
+                upkeep: 1.2,
+                division_energy: 0.5,
+                ..default
+            },
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::grid::store::CellRef;
+
+const YOUNG_COLOR: [f32; 3] = [0.3, 0.85, 0.35];
+const OLD_COLOR: [f32; 3] = [0.85, 0.2, 0.15];
+
+/// What the colour of a drawn cell shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// The inherited colour of the cell.
+    #[default]
+    Lineage,
+    /// The age of the cell, from green when newborn to red at the maximum age.
+    Age,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 2] = [ColorMode::Lineage, ColorMode::Age];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorMode::Lineage => "Lineage",
+            ColorMode::Age => "Age",
+        }
+    }
+
+    pub fn color(self, cell: &CellRef, max_age: u64) -> [f32; 3] {
+        match self {
+            ColorMode::Lineage => cell.color,
+            ColorMode::Age => {
+                let t = (cell.age as f32 / max_age.max(1) as f32).min(1.0);
+                std::array::from_fn(|i| YOUNG_COLOR[i] + (OLD_COLOR[i] - YOUNG_COLOR[i]) * t)
+            }
+        }
+    }
+}
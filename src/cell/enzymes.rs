@@ -0,0 +1,52 @@
+use crate::simulation::Rng;
+
+/// Metabolic pathways of a cell: how efficiently it turns every resource into energy.
+///
+/// Every level is in `0.0..=1.0`; the cell pays upkeep for the sum of its levels,
+/// so generalists are costlier than specialists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Enzymes {
+    /// Efficiency of turning light into energy.
+    pub photosynthesis: f32,
+    /// Efficiency of digesting organic food.
+    pub heterotrophy: f32,
+    /// Efficiency of oxidising minerals.
+    pub chemosynthesis: f32,
+}
+
+impl Enzymes {
+    pub fn total(&self) -> f32 {
+        self.photosynthesis + self.heterotrophy + self.chemosynthesis
+    }
+
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        for level in [
+            &mut self.photosynthesis,
+            &mut self.heterotrophy,
+            &mut self.chemosynthesis,
+        ] {
+            *level = (*level + rng.next_signed() * strength).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Takes every pathway from either parent with equal chance.
+    pub fn crossover(&self, other: &Enzymes, rng: &mut Rng) -> Enzymes {
+        let mut pick = |a: f32, b: f32| if rng.next_f32() < 0.5 { a } else { b };
+        Enzymes {
+            photosynthesis: pick(self.photosynthesis, other.photosynthesis),
+            heterotrophy: pick(self.heterotrophy, other.heterotrophy),
+            chemosynthesis: pick(self.chemosynthesis, other.chemosynthesis),
+        }
+    }
+}
+
+/// Feeds on light and organic food.
+impl Default for Enzymes {
+    fn default() -> Self {
+        Self {
+            photosynthesis: 1.0,
+            heterotrophy: 1.0,
+            chemosynthesis: 0.0,
+        }
+    }
+}
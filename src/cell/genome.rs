@@ -0,0 +1,392 @@
+use crate::{grid::signals::SIGNAL_CHANNELS, simulation::Rng};
+
+use super::{CellType, Enzymes};
+
+/// Heritable behaviour traits of a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Genome {
+    /// Chance per tick to attack a neighbouring cell, `0.0..=1.0`.
+    pub aggression: f32,
+    /// Chance per tick to feed on dead matter nearby, `0.0..=1.0`.
+    pub scavenging: f32,
+    /// Chance per tick to move to a neighbouring slot, `0.0..=1.0`.
+    pub motility: f32,
+    /// Chance per tick to bond with a kin neighbour and for a daughter to stay bonded,
+    /// `0.0..=1.0`; the lower it is, the more often bonds break.
+    pub adhesion: f32,
+    /// Chance per tick to mate with a compatible neighbour once the cell has enough
+    /// energy, `0.0..=1.0`.
+    pub mating: f32,
+    /// Chance to reject a plasmid injected by a neighbour, `0.0..=1.0`.
+    pub resistance: f32,
+    pub enzymes: Enzymes,
+    pub differentiation: Differentiation,
+    /// Signal chemicals emitted per tick, per channel.
+    pub emission: [f32; SIGNAL_CHANNELS],
+    /// How the cell reacts to the signal chemicals, per channel.
+    pub receptors: [Receptor; SIGNAL_CHANNELS],
+}
+
+/// Reaction of a cell to a signal chemical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Response {
+    #[default]
+    None,
+    /// Do not divide.
+    InhibitDivision,
+    /// Move up the gradient.
+    Attract,
+    /// Move down the gradient.
+    Repel,
+    /// Turn into the given type.
+    Differentiate(CellType),
+}
+
+impl Response {
+    pub const ALL: [Response; 4 + CellType::COUNT] = [
+        Response::None,
+        Response::InhibitDivision,
+        Response::Attract,
+        Response::Repel,
+        Response::Differentiate(CellType::Stem),
+        Response::Differentiate(CellType::Leaf),
+        Response::Differentiate(CellType::Root),
+        Response::Differentiate(CellType::Mover),
+        Response::Differentiate(CellType::Defender),
+        Response::Differentiate(CellType::Reproductive),
+    ];
+
+    pub fn name(self) -> String {
+        match self {
+            Response::None => "None".to_string(),
+            Response::InhibitDivision => "Inhibit division".to_string(),
+            Response::Attract => "Attract".to_string(),
+            Response::Repel => "Repel".to_string(),
+            Response::Differentiate(cell_type) => format!("Become {}", cell_type.name()),
+        }
+    }
+}
+
+/// Response triggered once the concentration of a channel in the slot reaches the threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Receptor {
+    pub threshold: f32,
+    pub response: Response,
+}
+
+impl Receptor {
+    pub fn is_triggered(&self, concentration: f32) -> bool {
+        self.response != Response::None && concentration >= self.threshold
+    }
+
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        self.threshold = (self.threshold + rng.next_signed() * strength * 10.0).clamp(0.0, 10.0);
+        if rng.next_f32() < strength {
+            self.response = Response::ALL[rng.next_index(Response::ALL.len())];
+        }
+    }
+}
+
+impl Default for Receptor {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            response: Response::None,
+        }
+    }
+}
+
+/// Inheritable rule choosing the type of a daughter from the type of its mother and
+/// the light falling into the slot it is born into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Differentiation {
+    /// Slots at least this bright count as lit.
+    pub light_threshold: f32,
+    /// Type of daughters born into lit slots, indexed by the type of the mother.
+    pub lit: [CellType; CellType::COUNT],
+    /// Type of daughters born into shaded slots, indexed by the type of the mother.
+    pub shaded: [CellType; CellType::COUNT],
+}
+
+impl Differentiation {
+    pub fn daughter_type(&self, mother: CellType, light: f32) -> CellType {
+        if light >= self.light_threshold {
+            self.lit[mother as usize]
+        } else {
+            self.shaded[mother as usize]
+        }
+    }
+
+    /// Every entry of the rule switches to a random type with a chance of `strength`.
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        self.light_threshold =
+            (self.light_threshold + rng.next_signed() * strength).clamp(0.0, 2.0);
+        for cell_type in self.lit.iter_mut().chain(self.shaded.iter_mut()) {
+            if rng.next_f32() < strength {
+                *cell_type = CellType::ALL[rng.next_index(CellType::COUNT)];
+            }
+        }
+    }
+}
+
+/// Daughters take the type of their mother.
+impl Default for Differentiation {
+    fn default() -> Self {
+        Self {
+            light_threshold: 0.5,
+            lit: CellType::ALL,
+            shaded: CellType::ALL,
+        }
+    }
+}
+
+impl Genome {
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        self.aggression = (self.aggression + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.scavenging = (self.scavenging + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.motility = (self.motility + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.adhesion = (self.adhesion + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.mating = (self.mating + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.resistance = (self.resistance + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.enzymes.mutate(strength, rng);
+        self.differentiation.mutate(strength, rng);
+        for emission in self.emission.iter_mut() {
+            *emission = (*emission + rng.next_signed() * strength).clamp(0.0, 1.0);
+        }
+        for receptor in self.receptors.iter_mut() {
+            receptor.mutate(strength, rng);
+        }
+    }
+
+    /// Genome of an offspring taking every gene from either parent with equal chance.
+    pub fn crossover(&self, other: &Genome, rng: &mut Rng) -> Genome {
+        let mut pick = |a: f32, b: f32| if rng.next_f32() < 0.5 { a } else { b };
+        let mut genome = Genome {
+            aggression: pick(self.aggression, other.aggression),
+            scavenging: pick(self.scavenging, other.scavenging),
+            motility: pick(self.motility, other.motility),
+            adhesion: pick(self.adhesion, other.adhesion),
+            mating: pick(self.mating, other.mating),
+            resistance: pick(self.resistance, other.resistance),
+            enzymes: self.enzymes,
+            differentiation: self.differentiation.clone(),
+            emission: self.emission,
+            receptors: self.receptors,
+        };
+
+        genome.differentiation.light_threshold = pick(
+            self.differentiation.light_threshold,
+            other.differentiation.light_threshold,
+        );
+        genome.enzymes = self.enzymes.crossover(&other.enzymes, rng);
+        let differentiation = &mut genome.differentiation;
+        for i in 0..CellType::COUNT {
+            if rng.next_f32() < 0.5 {
+                differentiation.lit[i] = other.differentiation.lit[i];
+            }
+            if rng.next_f32() < 0.5 {
+                differentiation.shaded[i] = other.differentiation.shaded[i];
+            }
+        }
+        for channel in 0..SIGNAL_CHANNELS {
+            if rng.next_f32() < 0.5 {
+                genome.emission[channel] = other.emission[channel];
+            }
+            if rng.next_f32() < 0.5 {
+                genome.receptors[channel] = other.receptors[channel];
+            }
+        }
+
+        genome
+    }
+
+    /// Mean absolute difference of the behaviour traits, enzyme levels and emissions
+    /// of two genomes, `0.0..=1.0`.
+    pub fn distance(&self, other: &Genome) -> f32 {
+        let traits = [
+            (self.aggression, other.aggression),
+            (self.scavenging, other.scavenging),
+            (self.motility, other.motility),
+            (self.adhesion, other.adhesion),
+            (self.mating, other.mating),
+            (self.resistance, other.resistance),
+        ];
+        let enzymes = [
+            (self.enzymes.photosynthesis, other.enzymes.photosynthesis),
+            (self.enzymes.heterotrophy, other.enzymes.heterotrophy),
+            (self.enzymes.chemosynthesis, other.enzymes.chemosynthesis),
+        ];
+        let emission = self.emission.into_iter().zip(other.emission);
+
+        let (sum, count) = traits
+            .into_iter()
+            .chain(enzymes)
+            .chain(emission)
+            .fold((0.0, 0), |(sum, count), (a, b)| {
+                (sum + (a - b).abs(), count + 1)
+            });
+        sum / count as f32
+    }
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self {
+            aggression: 0.0,
+            scavenging: 0.0,
+            motility: 0.0,
+            adhesion: 0.0,
+            mating: 0.0,
+            resistance: 0.0,
+            enzymes: Enzymes::default(),
+            differentiation: Differentiation::default(),
+            emission: [0.0; SIGNAL_CHANNELS],
+            receptors: [Receptor::default(); SIGNAL_CHANNELS],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Genome whose every trait differs from those of a parent built with another `base`.
+    fn parent(base: f32) -> Genome {
+        let mut lit = CellType::ALL;
+        lit.rotate_left(base as usize % CellType::COUNT);
+        Genome {
+            aggression: base + 0.01,
+            scavenging: base + 0.02,
+            motility: base + 0.03,
+            adhesion: base + 0.04,
+            mating: base + 0.05,
+            resistance: base + 0.06,
+            enzymes: Enzymes {
+                photosynthesis: base + 0.07,
+                heterotrophy: base + 0.08,
+                chemosynthesis: base + 0.09,
+            },
+            differentiation: Differentiation {
+                light_threshold: base + 0.1,
+                lit,
+                shaded: lit
+                    .map(|cell_type| CellType::ALL[CellType::COUNT - 1 - cell_type as usize]),
+            },
+            emission: [base + 0.11, base + 0.12, base + 0.13, base + 0.14],
+            receptors: [0.15, 0.16, 0.17, 0.18].map(|threshold| Receptor {
+                threshold: base + threshold,
+                response: Response::ALL[base as usize % Response::ALL.len()],
+            }),
+        }
+    }
+
+    #[test]
+    fn crossover_takes_every_trait_from_a_parent() {
+        let (a, b) = (parent(0.0), parent(1.0));
+        let mut from_b = 0;
+        let mut traits = 0;
+        for seed in 0..64 {
+            let child = a.crossover(&b, &mut Rng::new(seed));
+
+            let mut check = |from_a: bool, from_other: bool| {
+                assert!(from_a || from_other, "seed {seed}: {child:?}");
+                from_b += from_other as usize;
+                traits += 1;
+            };
+            let scalars = [
+                (child.aggression, a.aggression, b.aggression),
+                (child.scavenging, a.scavenging, b.scavenging),
+                (child.motility, a.motility, b.motility),
+                (child.adhesion, a.adhesion, b.adhesion),
+                (child.mating, a.mating, b.mating),
+                (child.resistance, a.resistance, b.resistance),
+                (
+                    child.enzymes.photosynthesis,
+                    a.enzymes.photosynthesis,
+                    b.enzymes.photosynthesis,
+                ),
+                (
+                    child.enzymes.heterotrophy,
+                    a.enzymes.heterotrophy,
+                    b.enzymes.heterotrophy,
+                ),
+                (
+                    child.enzymes.chemosynthesis,
+                    a.enzymes.chemosynthesis,
+                    b.enzymes.chemosynthesis,
+                ),
+                (
+                    child.differentiation.light_threshold,
+                    a.differentiation.light_threshold,
+                    b.differentiation.light_threshold,
+                ),
+            ];
+            for (child, a, b) in scalars {
+                check(child == a, child == b);
+            }
+            for i in 0..CellType::COUNT {
+                let (lit, shaded) = (
+                    child.differentiation.lit[i],
+                    child.differentiation.shaded[i],
+                );
+                check(
+                    lit == a.differentiation.lit[i],
+                    lit == b.differentiation.lit[i],
+                );
+                check(
+                    shaded == a.differentiation.shaded[i],
+                    shaded == b.differentiation.shaded[i],
+                );
+            }
+            for channel in 0..SIGNAL_CHANNELS {
+                let (emission, receptor) = (child.emission[channel], child.receptors[channel]);
+                check(
+                    emission == a.emission[channel],
+                    emission == b.emission[channel],
+                );
+                check(
+                    receptor == a.receptors[channel],
+                    receptor == b.receptors[channel],
+                );
+            }
+        }
+
+        // Both parents pass on their traits, about evenly.
+        assert!(
+            (traits / 3..traits * 2 / 3).contains(&from_b),
+            "{from_b} of {traits}"
+        );
+    }
+
+    #[test]
+    fn distance_is_the_mean_trait_difference() {
+        let a = Genome::default();
+        let mut b = a.clone();
+        assert_eq!(a.distance(&b), 0.0);
+
+        b.aggression = 1.0;
+        b.emission[0] = 0.5;
+        let traits = (6 + 3 + SIGNAL_CHANNELS) as f32;
+        assert_eq!(a.distance(&b), 1.5 / traits);
+        assert_eq!(b.distance(&a), a.distance(&b));
+
+        // A phototroph and a mineral eater differ even with the same behaviour.
+        let phototroph = Genome {
+            enzymes: Enzymes {
+                photosynthesis: 1.0,
+                heterotrophy: 0.0,
+                chemosynthesis: 0.0,
+            },
+            ..Genome::default()
+        };
+        let lithotroph = Genome {
+            enzymes: Enzymes {
+                photosynthesis: 0.0,
+                heterotrophy: 0.0,
+                chemosynthesis: 1.0,
+            },
+            ..phototroph.clone()
+        };
+        assert_eq!(phototroph.distance(&lithotroph), 2.0 / traits);
+    }
+}
@@ -0,0 +1,21 @@
+use std::fmt;
+
+/// Identifier of a cell, kept while it moves and never given to another cell.
+///
+/// Cells get one from the grid when they are placed on it; until then, like
+/// the cell of the paint tool, they hold the default identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CellId(pub u64);
+
+impl CellId {
+    /// Whether the cell was ever placed on a grid.
+    pub fn is_assigned(self) -> bool {
+        self != Self::default()
+    }
+}
+
+impl fmt::Display for CellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
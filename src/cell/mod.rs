@@ -2,19 +2,95 @@ use nalgebra::Vector2;
 
 use crate::{
     control::Camera,
-    grid::{constants::SIZE_RENDER_CELL_GRID, layout::Layout},
+    grid::{
+        constants::{SIZE_GRID, SIZE_RENDER_CELL_GRID},
+        field::Field,
+        store::{CellRef, CellStore},
+    },
     opengl::prelude::{get_location, GetId, Program, Shader, Vao, Vbo},
+    simulation::Rng,
 };
 
+pub use behaviour::Action;
+pub use brain::Brain;
+pub use cell_type::CellType;
+pub use color_mode::ColorMode;
+pub use enzymes::Enzymes;
+pub use genome::{Genome, Response};
+pub use id::CellId;
+pub use plasmid::Plasmid;
+pub use sensors::Observation;
+
+pub mod behaviour;
+pub mod brain;
+pub mod cell_type;
+pub mod color_mode;
+pub mod enzymes;
+pub mod genome;
+pub mod id;
+pub mod plasmid;
+pub mod sensors;
+
+const REMAINS_COLOR: [f32; 3] = [0.45, 0.4, 0.3];
+/// Dead matter below this mass is not drawn.
+const MIN_VISIBLE_REMAINS: f32 = 0.5;
+/// Dead matter of this mass and above is drawn at full size.
+const REMAINS_SPRITE_MASS: f32 = 16.0;
+
 #[derive(Debug, Clone)]
 pub struct Cell {
-    mass: f32,
-    energy: f32,
+    pub(crate) id: CellId,
+    pub(crate) mass: f32,
+    pub(crate) energy: f32,
+    /// Ticks lived.
+    pub(crate) age: u64,
     pub color: [f32; 3],
+    pub cell_type: CellType,
+    pub genome: Genome,
+    pub plasmids: Vec<Plasmid>,
+    /// Network choosing the actions of the cell instead of the rules of its genome.
+    pub brain: Option<Brain>,
+    /// State in a multi-state automaton; `0` stands for an empty slot.
+    pub state: u8,
 }
 
 impl Cell {
-    pub fn create_render_data(&self, pos: Vector2<usize>) -> [f32; 42] {
+    pub fn id(&self) -> CellId {
+        self.id
+    }
+
+    /// Shifts the heritable traits of a freshly born cell by up to `strength` and
+    /// decides which plasmids it inherits.
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        for channel in self.color.iter_mut() {
+            *channel = (*channel + rng.next_signed() * strength).clamp(0.0, 1.0);
+        }
+        self.genome.mutate(strength, rng);
+        if let Some(brain) = &mut self.brain {
+            brain.mutate(strength, rng);
+        }
+        self.inherit_plasmids(strength, rng);
+    }
+
+    pub fn create_render_data(
+        pos: Vector2<usize>,
+        cell_type: CellType,
+        color: [f32; 3],
+    ) -> [f32; 54] {
+        Self::create_quad(pos, color, cell_type as i32 as f32, 0.0)
+    }
+
+    /// Faded sprite of the dead matter lying in the slot; `amount` is in `0.0..=1.0`.
+    pub fn create_remains_render_data(pos: Vector2<usize>, amount: f32) -> [f32; 54] {
+        Self::create_quad(pos, REMAINS_COLOR, 0.0, amount.clamp(0.0, 1.0))
+    }
+
+    fn create_quad(
+        pos: Vector2<usize>,
+        color: [f32; 3],
+        cell_type: f32,
+        remains: f32,
+    ) -> [f32; 54] {
         let (x, y) = (
             pos.x as f32 * SIZE_RENDER_CELL_GRID,
             pos.y as f32 * SIZE_RENDER_CELL_GRID,
@@ -24,44 +100,56 @@ impl Cell {
             y,
             0.0,
             0.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
             x + SIZE_RENDER_CELL_GRID,
             y,
             1.0,
             0.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
             x,
             y + SIZE_RENDER_CELL_GRID,
             0.0,
             1.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
             x,
             y + SIZE_RENDER_CELL_GRID,
             0.0,
             1.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
             x + SIZE_RENDER_CELL_GRID,
             y + SIZE_RENDER_CELL_GRID,
             1.0,
             1.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
             x + SIZE_RENDER_CELL_GRID,
             y,
             1.0,
             0.0,
-            self.color[0],
-            self.color[1],
-            self.color[2],
+            color[0],
+            color[1],
+            color[2],
+            remains,
+            cell_type,
         ];
 
         vertices
@@ -78,16 +166,34 @@ impl Cell {
         (Vao(vao), Vbo(vbo))
     }
 
-    pub fn init_render_cells(layout_cells: &Layout<Cell>, vao: Vao, vbo: Vbo) -> usize {
+    pub fn init_render_cells(
+        cells: &CellStore,
+        remains: &Field,
+        color_mode: ColorMode,
+        max_age: u64,
+        vao: Vao,
+        vbo: Vbo,
+    ) -> usize {
         let mut vertices = vec![];
-        for (x, col) in layout_cells.iter().enumerate() {
-            for (y, cell) in col.iter().enumerate() {
-                if let Some(cell) = cell {
-                    vertices.extend(cell.create_render_data(Vector2::new(x, y)));
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                if remains[pos] >= MIN_VISIBLE_REMAINS {
+                    vertices.extend(Self::create_remains_render_data(
+                        pos,
+                        remains[pos] / REMAINS_SPRITE_MASS,
+                    ));
                 }
             }
         }
 
+        for pos in cells.positions() {
+            if let Some(cell) = cells.get(pos) {
+                let color = color_mode.color(&cell, max_age);
+                vertices.extend(Self::create_render_data(pos, cell.cell_type, color));
+            }
+        }
+
         if vertices.is_empty() {
             return 0;
         }
@@ -108,7 +214,7 @@ impl Cell {
                     2,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 7) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     0 as _,
                 );
                 gl::EnableVertexAttribArray(0);
@@ -118,7 +224,7 @@ impl Cell {
                     2,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 7) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     (2 * size_of::<f32>()) as _,
                 );
                 gl::EnableVertexAttribArray(1);
@@ -128,10 +234,30 @@ impl Cell {
                     3,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 7) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     (4 * size_of::<f32>()) as _,
                 );
                 gl::EnableVertexAttribArray(2);
+
+                gl::VertexAttribPointer(
+                    3,
+                    1,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 9) as i32,
+                    (7 * size_of::<f32>()) as _,
+                );
+                gl::EnableVertexAttribArray(3);
+
+                gl::VertexAttribPointer(
+                    4,
+                    1,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 9) as i32,
+                    (8 * size_of::<f32>()) as _,
+                );
+                gl::EnableVertexAttribArray(4);
             }
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl::BindVertexArray(0);
@@ -168,7 +294,7 @@ impl Cell {
                 );
                 gl::Uniform1f(get_location(program, "u_camera_scale"), camera.scale);
                 gl::Uniform1f(get_location(program, "u_time"), time);
-                gl::DrawArrays(gl::TRIANGLES, 0, (len_vec_vertices / 7) as i32);
+                gl::DrawArrays(gl::TRIANGLES, 0, (len_vec_vertices / 9) as i32);
                 gl::UseProgram(0);
             }
             gl::BindVertexArray(0);
@@ -176,12 +302,68 @@ impl Cell {
     }
 }
 
+impl CellRef<'_> {
+    /// Similarity of the lineage colours of two cells, `1.0` for identical ones.
+    pub fn kinship(&self, other: &CellRef) -> f32 {
+        let difference: f32 = (0..3).map(|i| (self.color[i] - other.color[i]).abs()).sum();
+        1.0 - difference / 3.0
+    }
+
+    /// Whether two cells are related closely enough to mate.
+    pub fn is_compatible(&self, other: &CellRef, min_kinship: f32, max_distance: f32) -> bool {
+        self.kinship(other) >= min_kinship && self.genome.distance(other.genome) <= max_distance
+    }
+}
+
 impl Default for Cell {
     fn default() -> Self {
         Self {
+            id: CellId::default(),
             mass: 16.0,
             energy: 256.0,
+            age: 0,
             color: [0.5; 3],
+            cell_type: CellType::default(),
+            genome: Genome::default(),
+            plasmids: vec![],
+            brain: None,
+            state: 1,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether a default cell may mate with one whose aggression is `aggression`
+    /// and whose colour is shifted by `shade`.
+    fn compatible(aggression: f32, shade: f32, min_kinship: f32, max_distance: f32) -> bool {
+        let mut cells = CellStore::new(2, 1);
+        let mut other = Cell::default();
+        other.genome.aggression = aggression;
+        other.color[0] += shade;
+        cells.insert(Vector2::new(0, 0), Cell::default());
+        cells.insert(Vector2::new(1, 0), other);
+
+        let (a, b) = (cells.get(Vector2::new(0, 0)), cells.get(Vector2::new(1, 0)));
+        a.unwrap()
+            .is_compatible(&b.unwrap(), min_kinship, max_distance)
+    }
+
+    #[test]
+    fn compatibility_respects_the_thresholds() {
+        // Genome distances are the mean difference over the behaviour traits, the
+        // enzyme levels and the emission channels.
+        let traits = 6.0 + 3.0 + Genome::default().emission.len() as f32;
+        let distance = 0.8 / traits;
+        assert!(compatible(0.8, 0.0, 1.0, distance));
+        assert!(compatible(0.8, 0.0, 1.0, distance + 1e-4));
+        assert!(!compatible(0.8, 0.0, 1.0, distance - 1e-4));
+        assert!(!compatible(1.0, 0.0, 1.0, distance));
+
+        // Kinship falls with the difference of the colours.
+        assert!(compatible(0.0, 0.3, 0.9, 0.0));
+        assert!(!compatible(0.0, 0.3, 0.95, 0.0));
+    }
+}
@@ -0,0 +1,125 @@
+use nalgebra::Vector2;
+
+use crate::{
+    grid::{
+        store::{CellMut, CellRef},
+        Grid,
+    },
+    simulation::Rng,
+};
+
+use super::Cell;
+
+/// Most plasmids a cell can carry at once.
+pub const MAX_PLASMIDS: usize = 4;
+
+/// Parasitic genetic element carried by a cell, like a virus or a plasmid.
+///
+/// Its genes mutate whenever it is copied, so it can co-evolve with its hosts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plasmid {
+    /// Lineage of the plasmid; a cell carries at most one plasmid of every strain.
+    pub strain: u32,
+    /// Chance per tick to be injected into a neighbouring cell, `0.0..=1.0`.
+    pub infectivity: f32,
+    /// Chance to be copied into a daughter of the host, `0.0..=1.0`.
+    pub inheritance: f32,
+    /// Energy the host spends per tick to maintain it.
+    pub upkeep: f32,
+}
+
+impl Plasmid {
+    pub fn mutate(&mut self, strength: f32, rng: &mut Rng) {
+        self.infectivity = (self.infectivity + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.inheritance = (self.inheritance + rng.next_signed() * strength).clamp(0.0, 1.0);
+        self.upkeep = (self.upkeep + rng.next_signed() * strength).max(0.0);
+    }
+}
+
+impl Default for Plasmid {
+    fn default() -> Self {
+        Self {
+            strain: 1,
+            infectivity: 0.05,
+            inheritance: 0.9,
+            upkeep: 0.5,
+        }
+    }
+}
+
+impl CellRef<'_> {
+    /// Energy spent per tick on maintaining the carried plasmids.
+    pub fn plasmid_upkeep(&self) -> f32 {
+        self.plasmids.iter().map(|plasmid| plasmid.upkeep).sum()
+    }
+
+    /// Plasmids the cell in `pos` injects into its neighbours this tick, without
+    /// changing the grid. An injection succeeds against the resistance of the target.
+    pub fn infections(
+        &self,
+        pos: Vector2<usize>,
+        grid: &Grid,
+        rng: &mut Rng,
+    ) -> Vec<(Vector2<usize>, Plasmid)> {
+        let mut infections = vec![];
+        for plasmid in self.plasmids {
+            if rng.next_f32() >= plasmid.infectivity {
+                continue;
+            }
+
+            let neighbours = grid.occupied_neighbours(pos);
+            if neighbours.is_empty() {
+                break;
+            }
+            let target = neighbours[rng.next_index(neighbours.len())];
+            let Some(host) = grid.cells.get(target) else {
+                continue;
+            };
+            if rng.next_f32() >= host.genome.resistance {
+                infections.push((target, *plasmid));
+            }
+        }
+
+        infections
+    }
+}
+
+impl CellMut<'_> {
+    pub fn carries(&self, strain: u32) -> bool {
+        self.plasmids.iter().any(|plasmid| plasmid.strain == strain)
+    }
+
+    /// Takes the plasmid in unless the cell already carries its strain or has no room.
+    pub fn infect(&mut self, plasmid: Plasmid) -> bool {
+        if self.plasmids.len() >= MAX_PLASMIDS || self.carries(plasmid.strain) {
+            return false;
+        }
+
+        self.plasmids.push(plasmid);
+        true
+    }
+}
+
+impl Cell {
+    /// Adds the plasmids of strains the cell lacks, as long as it has room; for
+    /// offspring carrying the plasmids of both parents.
+    pub fn merge_plasmids(&mut self, plasmids: &[Plasmid]) {
+        for &plasmid in plasmids {
+            if self.plasmids.len() >= MAX_PLASMIDS {
+                break;
+            }
+            if self.plasmids.iter().all(|own| own.strain != plasmid.strain) {
+                self.plasmids.push(plasmid);
+            }
+        }
+    }
+
+    /// Keeps every plasmid of a freshly born cell with its chance of inheritance.
+    pub(super) fn inherit_plasmids(&mut self, strength: f32, rng: &mut Rng) {
+        self.plasmids
+            .retain(|plasmid| rng.next_f32() < plasmid.inheritance);
+        for plasmid in self.plasmids.iter_mut() {
+            plasmid.mutate(strength, rng);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use nalgebra::Vector2;
+
+use crate::{
+    grid::{signals::SIGNAL_CHANNELS, store::CellRef, Grid},
+    simulation::Rng,
+    zone::ZoneKind,
+};
+
+/// Offsets of the neighbours in an [`Observation`]: left, top, right, bottom.
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Resource and signal levels of one slot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    pub light: f32,
+    pub nutrients: f32,
+    pub minerals: f32,
+    pub remains: f32,
+    pub signals: [f32; SIGNAL_CHANNELS],
+}
+
+impl Levels {
+    pub fn sample(grid: &Grid, pos: Vector2<usize>) -> Self {
+        Self {
+            light: grid.light(pos),
+            nutrients: grid.nutrients[pos],
+            minerals: grid.minerals[pos],
+            remains: grid.remains[pos],
+            signals: grid.signals.sample(pos),
+        }
+    }
+}
+
+/// What a cell perceives of one neighbouring slot.
+#[derive(Debug, Clone, Copy)]
+pub struct NeighbourView<'a> {
+    pub pos: Vector2<usize>,
+    pub zone: Option<ZoneKind>,
+    pub blocked: bool,
+    pub occupant: Option<CellRef<'a>>,
+    /// Kinship with the occupant, `0.0` for an empty slot.
+    pub kinship: f32,
+    pub bonded: bool,
+    pub levels: Levels,
+}
+
+impl NeighbourView<'_> {
+    /// Whether a cell can be born or moved into the slot.
+    pub fn is_free(&self) -> bool {
+        !self.blocked && self.occupant.is_none()
+    }
+}
+
+/// Differences of the resource levels across the slot, pointing towards higher
+/// levels; `x` grows to the right and `y` to the top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradients {
+    pub light: Vector2<f32>,
+    pub nutrients: Vector2<f32>,
+    pub minerals: Vector2<f32>,
+}
+
+/// Everything a cell perceives of its slot and its four neighbours.
+///
+/// Built without allocating, so it can be taken for every cell on every tick.
+#[derive(Debug, Clone)]
+pub struct Observation<'a> {
+    pub pos: Vector2<usize>,
+    pub zone: Option<ZoneKind>,
+    pub levels: Levels,
+    /// Left, top, right and bottom neighbours; `None` beyond the edge of the grid.
+    pub neighbours: [Option<NeighbourView<'a>>; 4],
+    pub gradients: Gradients,
+}
+
+impl<'a> Observation<'a> {
+    pub fn neighbours(&self) -> impl Iterator<Item = &NeighbourView<'a>> + Clone {
+        self.neighbours.iter().flatten()
+    }
+
+    pub fn free(&self) -> impl Iterator<Item = &NeighbourView<'a>> + Clone {
+        self.neighbours().filter(|n| n.is_free())
+    }
+
+    pub fn occupied(&self) -> impl Iterator<Item = (&NeighbourView<'a>, CellRef<'a>)> + Clone {
+        self.neighbours()
+            .filter_map(|n| n.occupant.map(|occupant| (n, occupant)))
+    }
+
+    /// Neighbouring cells the cell is not bonded to.
+    pub fn unbonded(&self) -> impl Iterator<Item = &NeighbourView<'a>> + Clone {
+        self.neighbours()
+            .filter(|n| n.occupant.is_some() && !n.bonded)
+    }
+
+    /// Mean kinship with the neighbouring cells, `0.0` without any.
+    pub fn mean_kinship(&self) -> f32 {
+        let (sum, count) = self.occupied().fold((0.0, 0), |(sum, count), (n, _)| {
+            (sum + n.kinship, count + 1)
+        });
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f32
+        }
+    }
+}
+
+impl CellRef<'_> {
+    /// Senses the slot `pos` the cell stands in and its neighbourhood.
+    pub fn observe<'a>(&self, pos: Vector2<usize>, grid: &'a Grid) -> Observation<'a> {
+        let levels = Levels::sample(grid, pos);
+        let neighbours = DIRECTIONS.map(|(dx, dy)| {
+            let n = Grid::shifted(pos, dx, dy)?;
+            let occupant = grid.cells.get(n);
+            Some(NeighbourView {
+                pos: n,
+                zone: grid.layout_zones[n].map(|zone| zone.kind),
+                blocked: grid.is_blocked(n),
+                occupant,
+                kinship: occupant.map_or(0.0, |other| self.kinship(&other)),
+                bonded: grid.bonds.is_bonded(pos, n),
+                levels: Levels::sample(grid, n),
+            })
+        });
+
+        // Walls and the edge of the grid read as the level of the slot itself.
+        let gradient = |level: fn(&Levels) -> f32| {
+            let side = |i: usize| match &neighbours[i] {
+                Some(n) if !n.blocked => level(&n.levels),
+                _ => level(&levels),
+            };
+            Vector2::new((side(2) - side(0)) / 2.0, (side(1) - side(3)) / 2.0)
+        };
+        let gradients = Gradients {
+            light: gradient(|levels| levels.light),
+            nutrients: gradient(|levels| levels.nutrients),
+            minerals: gradient(|levels| levels.minerals),
+        };
+
+        Observation {
+            pos,
+            zone: grid.layout_zones[pos].map(|zone| zone.kind),
+            levels,
+            neighbours,
+            gradients,
+        }
+    }
+}
+
+/// Picks one of the items uniformly without collecting them.
+pub fn choose<T>(items: impl Iterator<Item = T> + Clone, rng: &mut Rng) -> Option<T> {
+    let count = items.clone().count();
+    if count == 0 {
+        return None;
+    }
+
+    items.into_iter().nth(rng.next_index(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell::Cell, zone::Zone};
+
+    fn spawn(grid: &mut Grid, x: usize, y: usize, color: [f32; 3]) {
+        let cell = Cell {
+            color,
+            ..Cell::default()
+        };
+        grid.spawn_cell(Vector2::new(x, y), cell);
+    }
+
+    fn observe(grid: &Grid, x: usize, y: usize) -> Observation<'_> {
+        let pos = Vector2::new(x, y);
+        grid.cells.get(pos).unwrap().observe(pos, grid)
+    }
+
+    #[test]
+    fn sees_free_and_occupied_neighbours() {
+        let mut grid = Grid::new();
+        spawn(&mut grid, 5, 5, [0.5; 3]);
+        // A kin on the left, bonded, and a stranger on the right.
+        spawn(&mut grid, 4, 5, [0.5; 3]);
+        spawn(&mut grid, 6, 5, [0.5, 0.5, 0.2]);
+        grid.bonds.bond(Vector2::new(5, 5), Vector2::new(4, 5));
+        grid.set_zone(Vector2::new(5, 4), Some(Zone::new(ZoneKind::Soil)));
+
+        let observation = observe(&grid, 5, 5);
+        let [left, top, right, bottom] = observation.neighbours.map(Option::unwrap);
+
+        assert!(top.is_free() && bottom.is_free());
+        assert!(top.occupant.is_none() && top.kinship == 0.0 && top.zone.is_none());
+        assert_eq!(bottom.zone, Some(ZoneKind::Soil));
+
+        assert!(!left.is_free() && left.bonded);
+        assert_eq!(left.kinship, 1.0);
+        assert!(!right.is_free() && !right.bonded);
+        assert!((right.kinship - 0.9).abs() < 1e-6);
+
+        assert_eq!(observation.free().count(), 2);
+        assert_eq!(observation.unbonded().count(), 1);
+        assert!((observation.mean_kinship() - 0.95).abs() < 1e-6);
+    }
+
+    #[test]
+    fn walls_are_blocked_and_flatten_gradients() {
+        let mut grid = Grid::new();
+        spawn(&mut grid, 5, 5, [0.5; 3]);
+        grid.set_zone(Vector2::new(6, 5), Some(Zone::new(ZoneKind::Wall)));
+        for (x, y, nutrients) in [
+            (4, 5, 2.0),
+            (5, 5, 4.0),
+            (6, 5, 100.0),
+            (5, 6, 1.0),
+            (5, 4, 3.0),
+        ] {
+            grid.nutrients[Vector2::new(x, y)] = nutrients;
+        }
+
+        let observation = observe(&grid, 5, 5);
+        let right = observation.neighbours[2].unwrap();
+        assert!(right.blocked && !right.is_free());
+        assert_eq!(right.zone, Some(ZoneKind::Wall));
+
+        // The wall reads as the slot itself, whatever lies in it, and gradients
+        // point towards the higher level.
+        assert_eq!(observation.gradients.nutrients, Vector2::new(1.0, -1.0));
+    }
+
+    #[test]
+    fn the_edge_flattens_gradients() {
+        let mut grid = Grid::new();
+        spawn(&mut grid, 0, 0, [0.5; 3]);
+        for (x, y, minerals) in [(0, 0, 4.0), (1, 0, 6.0), (0, 1, 10.0)] {
+            grid.minerals[Vector2::new(x, y)] = minerals;
+        }
+
+        let observation = observe(&grid, 0, 0);
+        let [left, top, right, bottom] = observation.neighbours;
+        assert!(left.is_none() && bottom.is_none());
+        assert!(top.is_some() && right.is_some());
+        assert_eq!(observation.neighbours().count(), 2);
+        assert_eq!(observation.gradients.minerals, Vector2::new(1.0, 3.0));
+    }
+}
@@ -14,6 +14,8 @@ pub struct Mouse {
     pub old_position: Vector2<f32>,
     pub world_position: Vector2<f32>,
     pub grid_position: Vector2<usize>,
+    /// Slot under the pointer when a button was last pressed.
+    pub pressed_grid_position: Vector2<usize>,
     pub button: MouseButton,
     pub pressed: bool,
 }
@@ -25,6 +27,7 @@ impl Mouse {
             old_position: Default::default(),
             world_position: Default::default(),
             grid_position: Default::default(),
+            pressed_grid_position: Default::default(),
             button: MouseButton::Button1,
             pressed: false,
         }
@@ -51,33 +54,72 @@ impl Mouse {
         )
     }
 
-    pub fn event_tools(&self, tools: &Tools, grid: &mut Grid) {
+    pub fn event_tools(&self, tools: &mut Tools, grid: &mut Grid) {
         match tools.select_tools {
-            SelectTools::AddNewZone => grid.layout_zones[self.grid_position] = Some(tools.zone),
+            SelectTools::AddNewZone => {
+                grid.set_zone(self.grid_position, Some(tools.zone));
+            }
             SelectTools::AddNewCell => {
-                grid.layout_cells[self.grid_position] = Some(tools.cell.clone())
+                if !grid.is_blocked(self.grid_position) {
+                    grid.spawn_cell(self.grid_position, tools.cell.clone());
+                }
+            }
+            // Handled once per press in `event_press`, not along the drag.
+            SelectTools::Inspect | SelectTools::Stamp => {}
+            SelectTools::Select => {
+                tools.selection = Some((self.pressed_grid_position, self.grid_position));
             }
             SelectTools::None => {}
         }
     }
 
-    pub fn event_button(&mut self, button: &MouseButton, action: &Action) {
+    pub fn event_button(
+        &mut self,
+        button: &MouseButton,
+        action: &Action,
+        tools: &mut Tools,
+        grid: &mut Grid,
+    ) {
         self.button = *button;
 
         match action {
-            glfw::Action::Press => self.pressed = true,
+            glfw::Action::Press => {
+                self.pressed = true;
+                self.pressed_grid_position = self.grid_position;
+
+                if self.button == MouseButton::Button2 {
+                    self.event_press(tools, grid);
+                }
+            }
             _ => self.pressed = false,
         }
     }
 
-    pub fn event_action(&self, camera: &mut Camera, tools: &Tools, grid: &mut Grid) {
+    /// Tools acting once per click, even if the pointer does not move.
+    fn event_press(&self, tools: &mut Tools, grid: &mut Grid) {
+        match tools.select_tools {
+            SelectTools::Inspect => {
+                if let Some(cell) = grid.cells.get(self.grid_position) {
+                    tools.inspected = Some(cell.id);
+                }
+            }
+            SelectTools::Stamp => {
+                if let Some(pattern) = &tools.pattern {
+                    pattern.stamp(grid, self.grid_position, &tools.cell);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn event_action(&self, camera: &mut Camera, tools: &mut Tools, grid: &mut Grid) {
         if !self.pressed {
             return;
         }
 
         match self.button {
             glfw::MouseButton::Button3 => camera.mouse_move(self),
-            glfw::MouseButton::Button2 => self.event_tools(&tools, grid),
+            glfw::MouseButton::Button2 => self.event_tools(tools, grid),
             _ => {}
         }
     }
@@ -13,4 +13,8 @@ proc_opengl::init_program_shader! {
         vs: "./res/shaders/cell/cell.vert",
         fs: "./res/shaders/cell/cell.frag"
     }
+    bond {
+        vs: "./res/shaders/bond/bond.vert",
+        fs: "./res/shaders/bond/bond.frag"
+    }
 }
@@ -8,9 +8,20 @@ use init_res::Programs;
 use tools::{SelectTools, Tools};
 
 use crate::{
-    cell::Cell,
+    automaton::{
+        life::PRESETS as LIFE_PRESETS, table::PRESETS as TABLE_PRESETS, LifeRule, Pattern,
+        RuleTable,
+    },
+    cell::{
+        brain::{Thought, HIDDEN, INPUTS, INPUT_NAMES, OUTPUTS, OUTPUT_NAMES},
+        Brain, Cell, CellId, ColorMode, Observation,
+    },
     control::{Camera, Mouse},
-    grid::Grid,
+    grid::{bonds::Bonds, field::MAX_DIFFUSION_RATE, Grid},
+    simulation::{
+        clock::{Curve, Cycle},
+        Clock, Mode, Settings, Simulation, Statistics,
+    },
     zone::Zone,
 };
 
@@ -114,11 +125,13 @@ impl Game {
         let mut camera = Camera::new();
         let mut mouse = Mouse::new();
         let mut tools = Tools::default();
+        let mut simulation = Simulation::new(Self::seed());
         let mut time = 0.0;
 
         let mut grid = Grid::new();
         let (grid_vao, _) = grid.create_render_info();
         let (zone_vao, zone_vbo) = Zone::create_render_info();
+        let (bond_vao, bond_vbo) = Bonds::create_render_info();
         let (cell_vao, cell_vbo) = Cell::create_render_info();
 
         unsafe {
@@ -137,7 +150,7 @@ impl Game {
                     }
 
                     glfw::WindowEvent::MouseButton(button, action, _) => {
-                        mouse.event_button(&button, &action)
+                        mouse.event_button(&button, &action, &mut tools, &mut grid)
                     }
                     glfw::WindowEvent::Scroll(_, y) => camera.update_scale(y),
 
@@ -146,7 +159,7 @@ impl Game {
                         mouse.position = nalgebra::Vector2::new(x as f32, y as f32);
                         mouse.update_world_position(&camera, resolution);
                         mouse.update_grid_position();
-                        mouse.event_action(&mut camera, &tools, &mut grid);
+                        mouse.event_action(&mut camera, &mut tools, &mut grid);
                     }
 
                     _ => {}
@@ -159,9 +172,23 @@ impl Game {
                 }
             }
 
+            if simulation.running {
+                simulation.step(&mut grid);
+            }
+            if std::mem::take(&mut tools.leap_requested) {
+                simulation.leap(&mut grid, tools.leap);
+            }
+
+            let daylight = simulation.clock.daylight(simulation.tick);
+
             unsafe {
                 gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-                gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+                gl::ClearColor(
+                    0.02 + 0.08 * daylight,
+                    0.02 + 0.08 * daylight,
+                    0.05 + 0.05 * daylight,
+                    1.0,
+                );
 
                 grid.render_grid(&camera, resolution, &self.program_shader.grid, grid_vao);
 
@@ -173,10 +200,28 @@ impl Game {
                     &self.program_shader.zone,
                     len_vec_vertices,
                     zone_vao,
+                    daylight,
                 );
 
                 let len_vec_vertices =
-                    Cell::init_render_cells(&grid.layout_cells, cell_vao, cell_vbo);
+                    grid.bonds
+                        .init_render_bonds(&grid.cells, bond_vao, bond_vbo);
+                Bonds::render_bonds(
+                    &camera,
+                    resolution,
+                    &self.program_shader.bond,
+                    len_vec_vertices,
+                    bond_vao,
+                );
+
+                let len_vec_vertices = Cell::init_render_cells(
+                    &grid.cells,
+                    &grid.remains,
+                    tools.color_mode,
+                    simulation.settings.max_age,
+                    cell_vao,
+                    cell_vbo,
+                );
                 Cell::render_cell(
                     &camera,
                     resolution,
@@ -192,6 +237,7 @@ impl Game {
                     resolution,
                     &mouse,
                     &self.program_shader.zone,
+                    daylight,
                 );
                 tools.is_cell_to_render_cell(
                     &camera,
@@ -203,17 +249,64 @@ impl Game {
 
             time += 0.01;
 
-            Self::render_ui(&mut egui_components, &mouse, &mut tools);
+            Self::render_ui(
+                &mut egui_components,
+                &mouse,
+                &mut tools,
+                &mut simulation,
+                &grid,
+            );
 
             window.swap_buffers();
         }
     }
 
-    fn create_ui(ctx: &egui::Context, mouse: &Mouse, tools: &mut Tools) {
+    fn seed() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+
+    fn create_ui(
+        ctx: &egui::Context,
+        mouse: &Mouse,
+        tools: &mut Tools,
+        simulation: &mut Simulation,
+        grid: &Grid,
+    ) {
         egui::SidePanel::new(egui::containers::panel::Side::Right, "panel_tools").show(ctx, |ui| {
             ui.heading("Celleyor");
             ui.separator();
 
+            ui.checkbox(&mut simulation.running, "Run simulation");
+            ui.add(egui::Slider::new(&mut simulation.threads, 1..=32).text("Threads"));
+            Self::create_mode_ui(ui, &mut simulation.mode, &mut simulation.settings, tools);
+            ui.label(format!("Tick: {}", simulation.tick));
+            if simulation.mode != Mode::Ecosystem {
+                ui.label(format!("Active chunks: {}", simulation.active_chunks()));
+            }
+            ui.label(format!(
+                "Nutrients: {:.0}; minerals: {:.0}",
+                grid.nutrients.sum(),
+                grid.minerals.sum()
+            ));
+            Self::create_statistics_ui(ui, &simulation.statistics);
+            Self::create_clock_ui(ui, &mut simulation.clock, simulation.tick);
+            Self::create_signals_ui(ui, &mut simulation.settings, grid);
+            Self::create_mating_ui(ui, &mut simulation.settings);
+            Self::create_ageing_ui(ui, &mut simulation.settings);
+            ui.separator();
+
+            egui::ComboBox::from_id_salt("color_mode")
+                .selected_text(tools.color_mode.name())
+                .show_ui(ui, |ui| {
+                    for color_mode in ColorMode::ALL {
+                        ui.selectable_value(&mut tools.color_mode, color_mode, color_mode.name());
+                    }
+                });
+            ui.separator();
+
             ui.label("Tools:");
             egui::containers::ComboBox::from_id_source("select_tools")
                 .selected_text(format!("{:?}", tools.select_tools))
@@ -229,10 +322,27 @@ impl Game {
                         SelectTools::AddNewCell,
                         "Add new cell",
                     );
+                    ui.selectable_value(&mut tools.select_tools, SelectTools::Inspect, "Inspect");
+                    ui.selectable_value(
+                        &mut tools.select_tools,
+                        SelectTools::Stamp,
+                        "Stamp pattern",
+                    );
+                    ui.selectable_value(
+                        &mut tools.select_tools,
+                        SelectTools::Select,
+                        "Select area",
+                    );
                 });
 
             ui.separator();
             tools.render_ui(ui);
+            if tools.select_tools == SelectTools::Inspect {
+                Self::create_inspector_ui(ui, tools.inspected, grid, &simulation.settings);
+            }
+            if tools.select_tools == SelectTools::Select {
+                Self::create_export_ui(ui, tools, grid, &simulation.mode);
+            }
         });
 
         egui::TopBottomPanel::bottom("info_panel").show(ctx, |ui| {
@@ -246,7 +356,457 @@ impl Game {
         });
     }
 
-    fn render_ui(egui_components: &mut EguiComponents, mouse: &Mouse, tools: &mut Tools) {
+    fn create_mode_ui(
+        ui: &mut egui::Ui,
+        mode: &mut Mode,
+        settings: &mut Settings,
+        tools: &mut Tools,
+    ) {
+        let selected = match mode {
+            Mode::Ecosystem => "Ecosystem",
+            Mode::Life(_) => "Life-like automaton",
+            Mode::Table(_) => "Rule table",
+        };
+        egui::ComboBox::from_id_salt("mode")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(matches!(mode, Mode::Ecosystem), "Ecosystem")
+                    .clicked()
+                {
+                    *mode = Mode::Ecosystem;
+                }
+
+                let is_life = matches!(mode, Mode::Life(_));
+                if ui
+                    .selectable_label(is_life, "Life-like automaton")
+                    .clicked()
+                    && !is_life
+                {
+                    let rule = LifeRule::default();
+                    tools.rule = rule.to_string();
+                    *mode = Mode::Life(rule);
+                }
+
+                let is_table = matches!(mode, Mode::Table(_));
+                if ui.selectable_label(is_table, "Rule table").clicked() && !is_table {
+                    match TABLE_PRESETS[0].1.parse::<RuleTable>() {
+                        Ok(table) => {
+                            Self::select_paint_state(tools, &table, 1);
+                            *mode = Mode::Table(table);
+                        }
+                        Err(err) => tools.table_error = Some(err),
+                    }
+                }
+            });
+
+        match mode {
+            Mode::Ecosystem => {}
+            Mode::Life(rule) => Self::create_life_ui(ui, rule, settings, tools),
+            Mode::Table(table) => Self::create_table_ui(ui, table, tools),
+        }
+    }
+
+    fn create_life_ui(
+        ui: &mut egui::Ui,
+        rule: &mut LifeRule,
+        settings: &mut Settings,
+        tools: &mut Tools,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("Rule:");
+            ui.text_edit_singleline(&mut tools.rule);
+        });
+        ui.horizontal_wrapped(|ui| {
+            for (name, preset) in LIFE_PRESETS {
+                if ui.button(name).clicked() {
+                    tools.rule = preset.to_string();
+                }
+            }
+        });
+        match tools.rule.parse::<LifeRule>() {
+            Ok(parsed) => *rule = parsed,
+            Err(err) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, err);
+            }
+        }
+
+        egui::CollapsingHeader::new("HashLife").show(ui, |ui| {
+            ui.add(egui::Slider::new(&mut tools.leap, 0..=30).text("Leap, 2^n generations"));
+            ui.add(
+                egui::Slider::new(&mut settings.hashlife_memo_limit, 1 << 12..=1 << 24)
+                    .logarithmic(true)
+                    .text("Memo limit"),
+            );
+            if ui.button("Leap").clicked() {
+                tools.leap_requested = true;
+            }
+        });
+    }
+
+    fn create_table_ui(ui: &mut egui::Ui, table: &mut RuleTable, tools: &mut Tools) {
+        ui.label(format!("{}: {} states", table.name, table.n_states));
+
+        let mut loaded = None;
+        ui.horizontal_wrapped(|ui| {
+            for (name, text) in TABLE_PRESETS {
+                if ui.button(name).clicked() {
+                    loaded = Some(text.parse::<RuleTable>());
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut tools.table_path);
+            if ui.button("Load").clicked() {
+                let path = &tools.table_path;
+                loaded = Some(
+                    std::fs::read_to_string(path)
+                        .map_err(|err| format!("cannot read `{path}`: {err}"))
+                        .and_then(|text| text.parse()),
+                );
+            }
+        });
+
+        match loaded {
+            Some(Ok(parsed)) => {
+                *table = parsed;
+                tools.table_error = None;
+                Self::select_paint_state(tools, table, 1);
+            }
+            Some(Err(err)) => tools.table_error = Some(err),
+            None => {}
+        }
+        if let Some(err) = &tools.table_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, err);
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Paint state:");
+            for state in 1..table.n_states {
+                let [r, g, b] = table.colors[state].map(|channel| (channel * 255.0) as u8);
+                let button = egui::Button::new(state.to_string())
+                    .fill(egui::Color32::from_rgb(r, g, b))
+                    .selected(tools.cell.state as usize == state);
+                if ui.add(button).clicked() {
+                    Self::select_paint_state(tools, table, state);
+                }
+            }
+        });
+    }
+
+    /// Makes new cells placed with the cell tool start in `state` of the table.
+    fn select_paint_state(tools: &mut Tools, table: &RuleTable, state: usize) {
+        tools.cell.state = state as u8;
+        tools.cell.color = table.colors[state];
+    }
+
+    fn create_export_ui(ui: &mut egui::Ui, tools: &mut Tools, grid: &Grid, mode: &Mode) {
+        let Some((a, b)) = tools.selection else {
+            ui.label("Drag over the grid to select an area.");
+            return;
+        };
+        let pattern = Pattern::from_area(&grid.cells, a, b);
+        ui.label(format!(
+            "Selection: {}x{} from [{}; {}], {} cells",
+            pattern.width,
+            pattern.height,
+            a.x.min(b.x),
+            a.y.min(b.y),
+            pattern.live.len()
+        ));
+
+        let rule = match mode {
+            Mode::Life(rule) => Some(rule.to_string()),
+            _ => None,
+        };
+        let rle = pattern.to_rle(rule.as_deref());
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut tools.pattern_path);
+            if ui.button("Save RLE").clicked() {
+                let path = &tools.pattern_path;
+                tools.pattern_error = std::fs::write(path, &rle)
+                    .err()
+                    .map(|err| format!("cannot write `{path}`: {err}"));
+            }
+        });
+        if ui.button("Copy RLE").clicked() {
+            ui.output_mut(|output| output.copied_text = rle);
+        }
+        if let Some(err) = &tools.pattern_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, err);
+        }
+    }
+
+    fn create_statistics_ui(ui: &mut egui::Ui, statistics: &Statistics) {
+        ui.label(format!(
+            "Births: {} ({} sexual); deaths: {}",
+            statistics.births, statistics.sexual_births, statistics.deaths
+        ));
+        ui.label(format!(
+            "Predations: {}; failed attacks: {}",
+            statistics.predations, statistics.failed_attacks
+        ));
+        ui.label(format!("Moves: {}", statistics.moves));
+        ui.label(format!(
+            "Infections: {}; infected: {:.1}%",
+            statistics.infections,
+            statistics.prevalence * 100.0
+        ));
+    }
+
+    fn create_clock_ui(ui: &mut egui::Ui, clock: &mut Clock, tick: u64) {
+        let minutes = (clock.day_phase(tick) * 24.0 * 60.0) as u32;
+        ui.label(format!(
+            "Day {}, {:02}:{:02}",
+            clock.day(tick) + 1,
+            minutes / 60,
+            minutes % 60
+        ));
+
+        egui::CollapsingHeader::new("Environment clock").show(ui, |ui| {
+            ui.add(egui::Slider::new(&mut clock.day_length, 60..=12000).text("Day length, ticks"));
+            ui.add(egui::Slider::new(&mut clock.year_length, 1..=365).text("Year length, days"));
+
+            for (name, cycles) in [
+                ("Daily", &mut clock.daily),
+                ("Seasonal", &mut clock.seasonal),
+            ] {
+                ui.label(name);
+                Self::create_cycle_ui(ui, &format!("{name} light"), &mut cycles.light, 1.0);
+                Self::create_cycle_ui(
+                    ui,
+                    &format!("{name} temperature"),
+                    &mut cycles.temperature,
+                    30.0,
+                );
+                Self::create_cycle_ui(ui, &format!("{name} nutrients"), &mut cycles.nutrients, 1.0);
+            }
+        });
+    }
+
+    fn create_signals_ui(ui: &mut egui::Ui, settings: &mut Settings, grid: &Grid) {
+        egui::CollapsingHeader::new("Signal channels").show(ui, |ui| {
+            for (channel, params) in settings.signals.iter_mut().enumerate() {
+                ui.label(format!(
+                    "Channel {}: {:.1} in total",
+                    channel + 1,
+                    grid.signals.channel(channel).sum()
+                ));
+                ui.add(
+                    egui::Slider::new(&mut params.diffusion, 0.0..=MAX_DIFFUSION_RATE)
+                        .text("Diffusion"),
+                );
+                ui.add(egui::Slider::new(&mut params.decay, 0.0..=1.0).text("Decay"));
+            }
+        });
+    }
+
+    fn create_mating_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+        egui::CollapsingHeader::new("Mating").show(ui, |ui| {
+            ui.add(
+                egui::Slider::new(&mut settings.mating_energy, 0.0..=1024.0).text("Energy needed"),
+            );
+            ui.add(egui::Slider::new(&mut settings.mating_share, 0.0..=0.5).text("Energy share"));
+            ui.add(
+                egui::Slider::new(&mut settings.mating_kinship, 0.0..=1.0).text("Least kinship"),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.mating_distance, 0.0..=1.0)
+                    .text("Largest genome distance"),
+            );
+        });
+    }
+
+    fn create_ageing_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+        egui::CollapsingHeader::new("Ageing").show(ui, |ui| {
+            ui.checkbox(&mut settings.ageing, "Cells age");
+            ui.add(egui::Slider::new(&mut settings.max_age, 100..=100000).text("Lifespan, ticks"));
+            ui.add(egui::Slider::new(&mut settings.senescence, 0.0..=10.0).text("Senescence"));
+        });
+    }
+
+    fn create_inspector_ui(
+        ui: &mut egui::Ui,
+        inspected: Option<CellId>,
+        grid: &Grid,
+        settings: &Settings,
+    ) {
+        let Some(id) = inspected else {
+            ui.label("Click a cell to inspect it.");
+            return;
+        };
+        let Some(pos) = grid.cell_ids.position(id) else {
+            ui.label(format!("Cell {id} is dead."));
+            return;
+        };
+        let Some(cell) = grid.cells.get(pos) else {
+            return;
+        };
+
+        ui.label(format!("Cell {id} at [x: {}; y: {}]", pos.x, pos.y));
+        ui.label(format!("Type: {}", cell.cell_type.name()));
+        if settings.ageing {
+            ui.label(format!("Age: {} of {} ticks", cell.age, settings.max_age));
+        } else {
+            ui.label(format!("Age: {} ticks", cell.age));
+        }
+        ui.label(format!(
+            "Energy: {:.1}; mass: {:.1}",
+            cell.energy, cell.mass
+        ));
+        ui.label(format!("Bonds: {}", grid.bonds.bonded(pos).len()));
+        let strains: Vec<_> = cell
+            .plasmids
+            .iter()
+            .map(|plasmid| plasmid.strain.to_string())
+            .collect();
+        ui.label(format!("Plasmid strains: [{}]", strains.join(", ")));
+        ui.label(format!(
+            "Aggression: {:.2}; scavenging: {:.2}",
+            cell.genome.aggression, cell.genome.scavenging
+        ));
+        ui.label(format!(
+            "Motility: {:.2}; adhesion: {:.2}; mating: {:.2}",
+            cell.genome.motility, cell.genome.adhesion, cell.genome.mating
+        ));
+        ui.label(format!("Resistance: {:.2}", cell.genome.resistance));
+        let enzymes = &cell.genome.enzymes;
+        ui.label(format!(
+            "Photosynthesis: {:.2}; heterotrophy: {:.2}; chemosynthesis: {:.2}",
+            enzymes.photosynthesis, enzymes.heterotrophy, enzymes.chemosynthesis
+        ));
+
+        let observation = cell.observe(pos, grid);
+        Self::create_observation_ui(ui, &observation);
+
+        if let Some(brain) = &cell.brain {
+            ui.separator();
+            ui.label("Brain:");
+            let thought = brain.think(Brain::sense(&cell, &observation, settings));
+            Self::create_brain_ui(ui, brain, &thought);
+        }
+    }
+
+    fn create_observation_ui(ui: &mut egui::Ui, observation: &Observation) {
+        egui::CollapsingHeader::new("Senses").show(ui, |ui| {
+            let zone = observation.zone.map_or("None", |kind| kind.name());
+            let levels = &observation.levels;
+            ui.label(format!("Zone: {zone}"));
+            ui.label(format!(
+                "Light: {:.2}; nutrients: {:.1}; minerals: {:.1}; remains: {:.1}",
+                levels.light, levels.nutrients, levels.minerals, levels.remains
+            ));
+            let gradients = &observation.gradients;
+            for (name, gradient) in [
+                ("Light", gradients.light),
+                ("Nutrient", gradients.nutrients),
+                ("Mineral", gradients.minerals),
+            ] {
+                ui.label(format!(
+                    "{name} gradient: [x: {:.2}; y: {:.2}]",
+                    gradient.x, gradient.y
+                ));
+            }
+            ui.label(format!(
+                "Free neighbours: {}; cells around: {}; mean kinship: {:.2}",
+                observation.free().count(),
+                observation.occupied().count(),
+                observation.mean_kinship()
+            ));
+        });
+    }
+
+    /// Draws the network: weights as green (positive) or red (negative) links and
+    /// current activations as the brightness of the nodes.
+    fn create_brain_ui(ui: &mut egui::Ui, brain: &Brain, thought: &Thought) {
+        let (response, painter) =
+            ui.allocate_painter(vec2(ui.available_width(), 220.0), egui::Sense::hover());
+        let rect = response.rect;
+        let columns = [rect.left() + 70.0, rect.center().x, rect.right() - 60.0];
+        let node = |column: usize, i: usize, count: usize| {
+            Pos2::new(
+                columns[column],
+                rect.top() + rect.height() * (i as f32 + 0.5) / count as f32,
+            )
+        };
+        let link = |weight: f32| {
+            let alpha = (weight.abs().min(2.0) / 2.0 * 255.0) as u8;
+            let color = if weight >= 0.0 {
+                egui::Color32::from_rgba_unmultiplied(80, 200, 80, alpha)
+            } else {
+                egui::Color32::from_rgba_unmultiplied(220, 70, 70, alpha)
+            };
+            egui::Stroke::new(1.0, color)
+        };
+        let activation =
+            |value: f32| egui::Color32::from_gray(((value.tanh() + 1.0) * 127.5) as u8);
+        let font = egui::FontId::proportional(10.0);
+        let text_color = ui.visuals().text_color();
+
+        for h in 0..HIDDEN {
+            for i in 0..INPUTS {
+                painter.line_segment(
+                    [node(0, i, INPUTS), node(1, h, HIDDEN)],
+                    link(brain.hidden_weight(h, i)),
+                );
+            }
+            for o in 0..OUTPUTS {
+                painter.line_segment(
+                    [node(1, h, HIDDEN), node(2, o, OUTPUTS)],
+                    link(brain.output_weight(o, h)),
+                );
+            }
+        }
+
+        for (i, name) in INPUT_NAMES.iter().enumerate() {
+            let pos = node(0, i, INPUTS);
+            painter.circle_filled(pos, 4.0, activation(thought.inputs[i]));
+            painter.text(
+                pos - vec2(8.0, 0.0),
+                egui::Align2::RIGHT_CENTER,
+                name,
+                font.clone(),
+                text_color,
+            );
+        }
+        for h in 0..HIDDEN {
+            painter.circle_filled(node(1, h, HIDDEN), 4.0, activation(thought.hidden[h]));
+        }
+        for (o, name) in OUTPUT_NAMES.iter().enumerate() {
+            let pos = node(2, o, OUTPUTS);
+            painter.circle_filled(pos, 4.0, activation(thought.outputs[o]));
+            painter.text(
+                pos + vec2(8.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                format!("{name} {:.2}", thought.outputs[o]),
+                font.clone(),
+                text_color,
+            );
+        }
+    }
+
+    fn create_cycle_ui(ui: &mut egui::Ui, name: &str, cycle: &mut Cycle, max_amplitude: f32) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(name)
+                .selected_text(format!("{:?}", cycle.curve))
+                .show_ui(ui, |ui| {
+                    for curve in Curve::ALL {
+                        ui.selectable_value(&mut cycle.curve, curve, format!("{:?}", curve));
+                    }
+                });
+            ui.add(egui::Slider::new(&mut cycle.amplitude, 0.0..=max_amplitude).text(name));
+        });
+    }
+
+    fn render_ui(
+        egui_components: &mut EguiComponents,
+        mouse: &Mouse,
+        tools: &mut Tools,
+        simulation: &mut Simulation,
+        grid: &Grid,
+    ) {
         let EguiComponents {
             egui_ctx,
             painter,
@@ -256,7 +816,7 @@ impl Game {
 
         egui_ctx.begin_frame(egui_input_state.input.take());
 
-        Self::create_ui(egui_ctx, mouse, tools);
+        Self::create_ui(egui_ctx, mouse, tools, simulation, grid);
 
         let egui::FullOutput {
             platform_output,
@@ -1,8 +1,12 @@
+use egui_glfw::egui::{self, Slider};
+use nalgebra::Vector2;
+
 use crate::{
-    cell::Cell,
+    automaton::Pattern,
+    cell::{Brain, Cell, CellId, CellType, ColorMode, Plasmid, Response},
     control::{Camera, Mouse},
     opengl::prelude::{get_location, GetId, Program, Shader},
-    zone::Zone,
+    zone::{Environment, Zone, ZoneKind},
 };
 
 #[derive(Debug, Default)]
@@ -10,6 +14,27 @@ pub struct Tools {
     pub select_tools: SelectTools,
     pub zone: Zone,
     pub cell: Cell,
+    /// Cell shown in the inspector, followed as it moves.
+    pub inspected: Option<CellId>,
+    pub color_mode: ColorMode,
+    /// Rule string being edited for the automaton mode.
+    pub rule: String,
+    /// Path of the `.rule` file to load a rule table from.
+    pub table_path: String,
+    /// Why the last rule table failed to load.
+    pub table_error: Option<String>,
+    /// Generations to leap over with HashLife, as a power of two.
+    pub leap: u8,
+    /// Whether a leap was asked for and is yet to be made.
+    pub leap_requested: bool,
+    /// Pattern placed by the stamp tool.
+    pub pattern: Option<Pattern>,
+    /// Path of the pattern file to load or save.
+    pub pattern_path: String,
+    /// Why the last pattern failed to load or save.
+    pub pattern_error: Option<String>,
+    /// Corners of the area chosen with the select tool.
+    pub selection: Option<(Vector2<usize>, Vector2<usize>)>,
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -18,27 +43,179 @@ pub enum SelectTools {
     None,
     AddNewZone,
     AddNewCell,
+    Inspect,
+    Stamp,
+    Select,
 }
 
 impl Tools {
-    pub fn render_ui(&mut self, ui: &mut egui_glfw::egui::Ui) {
+    pub fn render_ui(&mut self, ui: &mut egui::Ui) {
         match self.select_tools {
-            SelectTools::None => {}
+            SelectTools::None | SelectTools::Inspect | SelectTools::Select => {}
+            SelectTools::Stamp => self.render_stamp_ui(ui),
             SelectTools::AddNewZone => {
+                let kind = self.zone.kind;
+                egui::ComboBox::from_id_salt("zone_kind")
+                    .selected_text(kind.name())
+                    .show_ui(ui, |ui| {
+                        for kind in ZoneKind::ALL {
+                            ui.selectable_value(&mut self.zone.kind, kind, kind.name());
+                        }
+                    });
+                if self.zone.kind != kind {
+                    self.zone = Zone::new(self.zone.kind);
+                }
+
                 ui.color_edit_button_rgb(&mut self.zone.color);
+                Self::render_environment_ui(ui, &mut self.zone.environment);
             }
             SelectTools::AddNewCell => {
+                egui::ComboBox::from_id_salt("cell_type")
+                    .selected_text(self.cell.cell_type.name())
+                    .show_ui(ui, |ui| {
+                        for cell_type in CellType::ALL {
+                            ui.selectable_value(
+                                &mut self.cell.cell_type,
+                                cell_type,
+                                cell_type.name(),
+                            );
+                        }
+                    });
+
                 ui.color_edit_button_rgb(&mut self.cell.color);
+
+                let mut neural = self.cell.brain.is_some();
+                ui.checkbox(&mut neural, "Neural brain");
+                if neural != self.cell.brain.is_some() {
+                    self.cell.brain = neural.then(Brain::default);
+                }
+                ui.add(Slider::new(&mut self.cell.genome.aggression, 0.0..=1.0).text("Aggression"));
+                ui.add(Slider::new(&mut self.cell.genome.scavenging, 0.0..=1.0).text("Scavenging"));
+                ui.add(Slider::new(&mut self.cell.genome.motility, 0.0..=1.0).text("Motility"));
+                ui.add(Slider::new(&mut self.cell.genome.adhesion, 0.0..=1.0).text("Adhesion"));
+                ui.add(Slider::new(&mut self.cell.genome.mating, 0.0..=1.0).text("Mating"));
+                ui.add(Slider::new(&mut self.cell.genome.resistance, 0.0..=1.0).text("Resistance"));
+                self.render_enzymes_ui(ui);
+                self.render_signalling_ui(ui);
+                self.render_plasmid_ui(ui);
             }
         }
     }
 
+    fn render_stamp_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.pattern_path);
+            if ui.button("Load").clicked() {
+                let path = &self.pattern_path;
+                let pattern = std::fs::read_to_string(path)
+                    .map_err(|err| format!("cannot read `{path}`: {err}"))
+                    .and_then(|text| text.parse::<Pattern>());
+                match pattern {
+                    Ok(pattern) => {
+                        self.pattern = Some(pattern);
+                        self.pattern_error = None;
+                    }
+                    Err(err) => self.pattern_error = Some(err),
+                }
+            }
+        });
+        if let Some(err) = &self.pattern_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, err);
+        }
+        if let Some(pattern) = &self.pattern {
+            ui.label(format!(
+                "{}: {}x{}, {} cells",
+                pattern.name.as_deref().unwrap_or("Pattern"),
+                pattern.width,
+                pattern.height,
+                pattern.live.len()
+            ));
+        }
+        ui.horizontal(|ui| {
+            ui.label("Colour:");
+            ui.color_edit_button_rgb(&mut self.cell.color);
+        });
+    }
+
+    fn render_enzymes_ui(&mut self, ui: &mut egui::Ui) {
+        let enzymes = &mut self.cell.genome.enzymes;
+        egui::CollapsingHeader::new("Enzymes").show(ui, |ui| {
+            ui.add(Slider::new(&mut enzymes.photosynthesis, 0.0..=1.0).text("Photosynthesis"));
+            ui.add(Slider::new(&mut enzymes.heterotrophy, 0.0..=1.0).text("Heterotrophy"));
+            ui.add(Slider::new(&mut enzymes.chemosynthesis, 0.0..=1.0).text("Chemosynthesis"));
+        });
+    }
+
+    fn render_signalling_ui(&mut self, ui: &mut egui::Ui) {
+        let genome = &mut self.cell.genome;
+        egui::CollapsingHeader::new("Signalling").show(ui, |ui| {
+            for (channel, (emission, receptor)) in genome
+                .emission
+                .iter_mut()
+                .zip(genome.receptors.iter_mut())
+                .enumerate()
+            {
+                ui.label(format!("Channel {}", channel + 1));
+                ui.add(Slider::new(emission, 0.0..=1.0).text("Emission"));
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_salt(("receptor", channel))
+                        .selected_text(receptor.response.name())
+                        .show_ui(ui, |ui| {
+                            for response in Response::ALL {
+                                ui.selectable_value(
+                                    &mut receptor.response,
+                                    response,
+                                    response.name(),
+                                );
+                            }
+                        });
+                    ui.add(Slider::new(&mut receptor.threshold, 0.0..=10.0).text("Threshold"));
+                });
+            }
+        });
+    }
+
+    fn render_plasmid_ui(&mut self, ui: &mut egui::Ui) {
+        let plasmids = &mut self.cell.plasmids;
+        egui::CollapsingHeader::new("Plasmid").show(ui, |ui| {
+            let carried = !plasmids.is_empty();
+            let mut infected = carried;
+            ui.checkbox(&mut infected, "Carries a plasmid");
+            if infected != carried {
+                *plasmids = if infected {
+                    vec![Plasmid::default()]
+                } else {
+                    vec![]
+                };
+            }
+
+            if let Some(plasmid) = plasmids.first_mut() {
+                ui.add(Slider::new(&mut plasmid.strain, 1..=16).text("Strain"));
+                ui.add(Slider::new(&mut plasmid.infectivity, 0.0..=1.0).text("Infectivity"));
+                ui.add(Slider::new(&mut plasmid.inheritance, 0.0..=1.0).text("Inheritance"));
+                ui.add(Slider::new(&mut plasmid.upkeep, 0.0..=5.0).text("Upkeep"));
+            }
+        });
+    }
+
+    fn render_environment_ui(ui: &mut egui::Ui, env: &mut Environment) {
+        ui.add(Slider::new(&mut env.light, 0.0..=1.0).text("Light"));
+        ui.add(Slider::new(&mut env.temperature, -20.0..=60.0).text("Temperature"));
+        ui.add(Slider::new(&mut env.nutrient_supply, 0.0..=2.0).text("Nutrient supply"));
+        ui.add(Slider::new(&mut env.mineral_supply, 0.0..=2.0).text("Mineral supply"));
+        ui.add(Slider::new(&mut env.toxicity, 0.0..=2.0).text("Toxicity"));
+        ui.add(Slider::new(&mut env.mutation_rate, 0.0..=10.0).text("Mutation rate"));
+        ui.add(Slider::new(&mut env.movement_cost, 0.0..=10.0).text("Movement cost"));
+    }
+
     pub fn is_zone_to_render_zone(
         &self,
         camera: &Camera,
         resolution: (f32, f32),
         mouse: &Mouse,
         program: &Program<Shader>,
+        daylight: f32,
     ) {
         if let SelectTools::AddNewZone = self.select_tools {
             let zone = self.zone;
@@ -65,7 +242,7 @@ impl Tools {
                         2,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 8) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         0 as _,
                     );
                     gl::EnableVertexAttribArray(0);
@@ -75,7 +252,7 @@ impl Tools {
                         2,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 8) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         (2 * size_of::<f32>()) as _,
                     );
                     gl::EnableVertexAttribArray(1);
@@ -85,7 +262,7 @@ impl Tools {
                         3,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 8) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         (4 * size_of::<f32>()) as _,
                     );
                     gl::EnableVertexAttribArray(2);
@@ -95,10 +272,20 @@ impl Tools {
                         1,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 8) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         (7 * size_of::<f32>()) as _,
                     );
                     gl::EnableVertexAttribArray(3);
+
+                    gl::VertexAttribPointer(
+                        4,
+                        1,
+                        gl::FLOAT,
+                        gl::FALSE,
+                        (size_of::<f32>() * 9) as i32,
+                        (8 * size_of::<f32>()) as _,
+                    );
+                    gl::EnableVertexAttribArray(4);
                 }
                 gl::BindBuffer(gl::ARRAY_BUFFER, 0);
 
@@ -115,7 +302,8 @@ impl Tools {
                         camera.position.y,
                     );
                     gl::Uniform1f(get_location(program, "u_camera_scale"), camera.scale);
-                    gl::DrawArrays(gl::TRIANGLES, 0, (vertices_zone.len() / 8) as _);
+                    gl::Uniform1f(get_location(program, "u_daylight"), daylight);
+                    gl::DrawArrays(gl::TRIANGLES, 0, (vertices_zone.len() / 9) as _);
                 }
                 gl::BindVertexArray(0);
 
@@ -136,7 +324,8 @@ impl Tools {
             let cell = &self.cell;
 
             let (cell_vao, cell_vbo) = Cell::create_render_info();
-            let vertices = cell.create_render_data(mouse.grid_position);
+            let vertices =
+                Cell::create_render_data(mouse.grid_position, cell.cell_type, cell.color);
 
             unsafe {
                 gl::BindVertexArray(cell_vao.0);
@@ -154,7 +343,7 @@ impl Tools {
                         2,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 7) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         0 as _,
                     );
                     gl::EnableVertexAttribArray(0);
@@ -164,7 +353,7 @@ impl Tools {
                         2,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 7) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         (2 * size_of::<f32>()) as _,
                     );
                     gl::EnableVertexAttribArray(1);
@@ -174,10 +363,30 @@ impl Tools {
                         3,
                         gl::FLOAT,
                         gl::FALSE,
-                        (size_of::<f32>() * 7) as i32,
+                        (size_of::<f32>() * 9) as i32,
                         (4 * size_of::<f32>()) as _,
                     );
                     gl::EnableVertexAttribArray(2);
+
+                    gl::VertexAttribPointer(
+                        3,
+                        1,
+                        gl::FLOAT,
+                        gl::FALSE,
+                        (size_of::<f32>() * 9) as i32,
+                        (7 * size_of::<f32>()) as _,
+                    );
+                    gl::EnableVertexAttribArray(3);
+
+                    gl::VertexAttribPointer(
+                        4,
+                        1,
+                        gl::FLOAT,
+                        gl::FALSE,
+                        (size_of::<f32>() * 9) as i32,
+                        (8 * size_of::<f32>()) as _,
+                    );
+                    gl::EnableVertexAttribArray(4);
                 }
                 gl::BindBuffer(gl::ARRAY_BUFFER, 0);
 
@@ -195,7 +404,7 @@ impl Tools {
                     );
                     gl::Uniform1f(get_location(program, "u_camera_scale"), camera.scale);
                     gl::Uniform1f(get_location(program, "u_time"), 0.0);
-                    gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len() / 7) as _);
+                    gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len() / 9) as _);
                 }
                 gl::BindVertexArray(0);
 
@@ -0,0 +1,52 @@
+use std::ops::Range;
+
+use nalgebra::Vector2;
+
+use super::constants::SIZE_GRID;
+
+/// Maps bands of adjacent columns of the grid, the outer index of
+/// [`super::layout::Layout`], on up to `threads` threads.
+///
+/// These are the row bands of the storage: slots are laid out column by column,
+/// so a band of columns is one contiguous run of memory per thread, as rows are
+/// in a row-major buffer. Bands of the grid's rows would interleave the threads
+/// within every column.
+///
+/// Each band reads the shared state of the previous tick and returns what to
+/// write into the next one. The results are joined in column order, so they are
+/// the same whatever the number of threads.
+pub fn map_bands<T, F>(threads: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(Range<usize>) -> Vec<T> + Sync,
+{
+    let columns = SIZE_GRID[0];
+    let threads = threads.clamp(1, columns);
+    if threads == 1 {
+        return f(0..columns);
+    }
+
+    let width = columns.div_ceil(threads);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..columns)
+            .step_by(width)
+            .map(|start| scope.spawn(move || f(start..(start + width).min(columns))))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+/// The part of `positions`, sorted by column, lying within the band.
+pub fn in_band(positions: &[Vector2<usize>], band: Range<usize>) -> &[Vector2<usize>] {
+    let start = positions.partition_point(|pos| pos.x < band.start);
+    let end = positions.partition_point(|pos| pos.x < band.end);
+    &positions[start..end]
+}
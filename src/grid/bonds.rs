@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+
+use nalgebra::Vector2;
+
+use crate::{
+    control::Camera,
+    opengl::prelude::{get_location, GetId, Program, Shader, Vao, Vbo},
+};
+
+use super::{
+    constants::{SIZE_GRID, SIZE_RENDER_CELL_GRID},
+    store::CellStore,
+};
+
+const LEFT: u8 = 0b0001;
+const TOP: u8 = 0b0010;
+const RIGHT: u8 = 0b0100;
+const BOTTOM: u8 = 0b1000;
+
+/// Half of the thickness of a drawn bond, relative to the size of a slot.
+const HALF_THICKNESS: f32 = 0.12;
+
+/// Bonds between orthogonally neighbouring cells.
+///
+/// Every slot keeps a mask of its bonds using the same bits as the zone
+/// borders (left, top, right, bottom); a bond is always set on both ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bonds {
+    masks: Vec<u8>,
+}
+
+impl Bonds {
+    pub fn new() -> Self {
+        Self {
+            masks: vec![0; SIZE_GRID[0] * SIZE_GRID[1]],
+        }
+    }
+
+    pub fn mask(&self, pos: Vector2<usize>) -> u8 {
+        self.masks[Self::offset(pos)]
+    }
+
+    pub fn is_bonded(&self, a: Vector2<usize>, b: Vector2<usize>) -> bool {
+        Self::direction(a, b).is_some_and(|bit| self.mask(a) & bit != 0)
+    }
+
+    pub fn bond(&mut self, a: Vector2<usize>, b: Vector2<usize>) {
+        if let (Some(ab), Some(ba)) = (Self::direction(a, b), Self::direction(b, a)) {
+            self.masks[Self::offset(a)] |= ab;
+            self.masks[Self::offset(b)] |= ba;
+        }
+    }
+
+    pub fn unbond(&mut self, a: Vector2<usize>, b: Vector2<usize>) {
+        if let (Some(ab), Some(ba)) = (Self::direction(a, b), Self::direction(b, a)) {
+            self.masks[Self::offset(a)] &= !ab;
+            self.masks[Self::offset(b)] &= !ba;
+        }
+    }
+
+    pub fn unbond_all(&mut self, pos: Vector2<usize>) {
+        for other in self.bonded(pos) {
+            self.unbond(pos, other);
+        }
+    }
+
+    /// Slots the cell in `pos` is bonded to.
+    pub fn bonded(&self, pos: Vector2<usize>) -> Vec<Vector2<usize>> {
+        let mask = self.mask(pos);
+        let (x, y) = (pos.x, pos.y);
+        let mut bonded = vec![];
+        if mask & LEFT != 0 {
+            bonded.push(Vector2::new(x - 1, y));
+        }
+        if mask & TOP != 0 {
+            bonded.push(Vector2::new(x, y + 1));
+        }
+        if mask & RIGHT != 0 {
+            bonded.push(Vector2::new(x + 1, y));
+        }
+        if mask & BOTTOM != 0 {
+            bonded.push(Vector2::new(x, y - 1));
+        }
+
+        bonded
+    }
+
+    /// All slots connected to `pos` through bonds, `pos` included.
+    pub fn body(&self, pos: Vector2<usize>) -> Vec<Vector2<usize>> {
+        let mut body = vec![pos];
+        let mut visited = HashSet::from([pos]);
+        let mut i = 0;
+        while i < body.len() {
+            for other in self.bonded(body[i]) {
+                if visited.insert(other) {
+                    body.push(other);
+                }
+            }
+            i += 1;
+        }
+
+        body
+    }
+
+    /// Moves the bonds of the slots in `from` to the slots in `to`, which must be disjoint
+    /// from the rest of the bonded bodies.
+    pub fn relocate(&mut self, from: &[Vector2<usize>], to: &[Vector2<usize>]) {
+        let masks: Vec<u8> = from.iter().map(|&pos| self.mask(pos)).collect();
+        for &pos in from {
+            self.masks[Self::offset(pos)] = 0;
+        }
+        for (&pos, mask) in to.iter().zip(masks) {
+            self.masks[Self::offset(pos)] = mask;
+        }
+    }
+
+    fn direction(from: Vector2<usize>, to: Vector2<usize>) -> Option<u8> {
+        match (
+            to.x as isize - from.x as isize,
+            to.y as isize - from.y as isize,
+        ) {
+            (-1, 0) => Some(LEFT),
+            (0, 1) => Some(TOP),
+            (1, 0) => Some(RIGHT),
+            (0, -1) => Some(BOTTOM),
+            _ => None,
+        }
+    }
+
+    fn offset(pos: Vector2<usize>) -> usize {
+        pos.x * SIZE_GRID[1] + pos.y
+    }
+
+    /// Bar from the centre of `a` to the centre of `b`, coloured like both cells.
+    pub fn create_render_data(a: Vector2<usize>, b: Vector2<usize>, color: [f32; 3]) -> [f32; 42] {
+        let (ax, ay) = (a.x as f32 + 0.5, a.y as f32 + 0.5);
+        let (bx, by) = (b.x as f32 + 0.5, b.y as f32 + 0.5);
+        let (nx, ny) = (
+            (ay - by).abs() * HALF_THICKNESS,
+            (ax - bx).abs() * HALF_THICKNESS,
+        );
+
+        let corner = |x: f32, y: f32| (x * SIZE_RENDER_CELL_GRID, y * SIZE_RENDER_CELL_GRID);
+        let (x0, y0) = corner(ax - nx, ay - ny);
+        let (x1, y1) = corner(bx - nx, by - ny);
+        let (x2, y2) = corner(ax + nx, ay + ny);
+        let (x3, y3) = corner(bx + nx, by + ny);
+
+        [
+            x0, y0, 0.0, 0.0, color[0], color[1], color[2], x1, y1, 1.0, 0.0, color[0], color[1],
+            color[2], x2, y2, 0.0, 1.0, color[0], color[1], color[2], x2, y2, 0.0, 1.0, color[0],
+            color[1], color[2], x3, y3, 1.0, 1.0, color[0], color[1], color[2], x1, y1, 1.0, 0.0,
+            color[0], color[1], color[2],
+        ]
+    }
+
+    pub fn create_render_info() -> (Vao, Vbo) {
+        let mut vao @ mut vbo = 0;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+        }
+
+        (Vao(vao), Vbo(vbo))
+    }
+
+    pub fn init_render_bonds(&self, cells: &CellStore, vao: Vao, vbo: Vbo) -> usize {
+        let mut vertices = vec![];
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                let mask = self.mask(pos);
+                for (bit, other) in [(RIGHT, (x + 1, y)), (TOP, (x, y + 1))] {
+                    let other = Vector2::new(other.0, other.1);
+                    if mask & bit == 0 {
+                        continue;
+                    }
+
+                    if let (Some(a), Some(b)) = (cells.get(pos), cells.get(other)) {
+                        let color = [
+                            (a.color[0] + b.color[0]) / 2.0,
+                            (a.color[1] + b.color[1]) / 2.0,
+                            (a.color[2] + b.color[2]) / 2.0,
+                        ];
+                        vertices.extend(Self::create_render_data(pos, other, color));
+                    }
+                }
+            }
+        }
+
+        if vertices.is_empty() {
+            return 0;
+        }
+
+        unsafe {
+            gl::BindVertexArray(vao.0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo.0);
+            {
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    (vertices.len() * size_of::<f32>()) as isize,
+                    &vertices[0] as *const f32 as _,
+                    gl::DYNAMIC_DRAW,
+                );
+
+                gl::VertexAttribPointer(
+                    0,
+                    2,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 7) as i32,
+                    0 as _,
+                );
+                gl::EnableVertexAttribArray(0);
+
+                gl::VertexAttribPointer(
+                    1,
+                    2,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 7) as i32,
+                    (2 * size_of::<f32>()) as _,
+                );
+                gl::EnableVertexAttribArray(1);
+
+                gl::VertexAttribPointer(
+                    2,
+                    3,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 7) as i32,
+                    (4 * size_of::<f32>()) as _,
+                );
+                gl::EnableVertexAttribArray(2);
+            }
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+
+        vertices.len()
+    }
+
+    pub fn render_bonds(
+        camera: &Camera,
+        resolution: (f32, f32),
+        program: &Program<Shader>,
+        len_vec_vertices: usize,
+        vao: Vao,
+    ) {
+        if len_vec_vertices == 0 {
+            return;
+        }
+
+        unsafe {
+            gl::BindVertexArray(vao.0);
+            {
+                gl::UseProgram(program.id());
+                gl::Uniform2f(
+                    get_location(program, "u_resolution"),
+                    resolution.0,
+                    resolution.1,
+                );
+                gl::Uniform2f(
+                    get_location(program, "u_camera_pos"),
+                    camera.position.x,
+                    camera.position.y,
+                );
+                gl::Uniform1f(get_location(program, "u_camera_scale"), camera.scale);
+                gl::DrawArrays(gl::TRIANGLES, 0, (len_vec_vertices / 7) as i32);
+                gl::UseProgram(0);
+            }
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: usize, y: usize) -> Vector2<usize> {
+        Vector2::new(x, y)
+    }
+
+    fn sorted(mut positions: Vec<Vector2<usize>>) -> Vec<Vector2<usize>> {
+        positions.sort_by_key(|pos| (pos.x, pos.y));
+        positions
+    }
+
+    #[test]
+    fn bonds_are_symmetric() {
+        let mut bonds = Bonds::new();
+        let centre = pos(5, 5);
+        for other in [pos(4, 5), pos(5, 6), pos(6, 5), pos(5, 4)] {
+            bonds.bond(centre, other);
+            assert!(bonds.is_bonded(centre, other));
+            assert!(bonds.is_bonded(other, centre));
+        }
+        assert_eq!(bonds.mask(centre), LEFT | TOP | RIGHT | BOTTOM);
+
+        // Only orthogonal neighbours can bond.
+        bonds.bond(centre, pos(6, 6));
+        bonds.bond(centre, pos(7, 5));
+        assert_eq!(bonds.mask(pos(6, 6)), 0);
+        assert_eq!(bonds.mask(pos(7, 5)), 0);
+
+        bonds.unbond(pos(5, 6), centre);
+        assert!(!bonds.is_bonded(centre, pos(5, 6)));
+        assert_eq!(bonds.mask(pos(5, 6)), 0);
+
+        bonds.unbond_all(centre);
+        assert_eq!(bonds, Bonds::new());
+    }
+
+    #[test]
+    fn body_follows_bonds() {
+        let mut bonds = Bonds::new();
+        // A ring of four with a tail, and a pair bonded to neither.
+        let ring = [pos(1, 1), pos(2, 1), pos(2, 2), pos(1, 2)];
+        for (i, &a) in ring.iter().enumerate() {
+            bonds.bond(a, ring[(i + 1) % ring.len()]);
+        }
+        bonds.bond(pos(2, 2), pos(3, 2));
+        bonds.bond(pos(1, 3), pos(2, 3));
+
+        let body = bonds.body(pos(3, 2));
+        assert_eq!(body[0], pos(3, 2));
+        assert_eq!(sorted(body), sorted([&ring[..], &[pos(3, 2)]].concat()));
+        assert_eq!(sorted(bonds.body(pos(2, 3))), [pos(1, 3), pos(2, 3)]);
+        assert_eq!(bonds.body(pos(8, 8)), [pos(8, 8)]);
+    }
+
+    #[test]
+    fn relocate_moves_the_bonds() {
+        let mut bonds = Bonds::new();
+        bonds.bond(pos(1, 1), pos(2, 1));
+        bonds.bond(pos(2, 1), pos(2, 2));
+        bonds.bond(pos(5, 5), pos(5, 6));
+
+        // One slot to the right, overlapping the old place.
+        let from = bonds.body(pos(1, 1));
+        let to: Vec<_> = from.iter().map(|p| pos(p.x + 1, p.y)).collect();
+        bonds.relocate(&from, &to);
+
+        let mut expected = Bonds::new();
+        expected.bond(pos(2, 1), pos(3, 1));
+        expected.bond(pos(3, 1), pos(3, 2));
+        expected.bond(pos(5, 5), pos(5, 6));
+        assert_eq!(bonds, expected);
+    }
+}
@@ -0,0 +1,135 @@
+use std::ops::Range;
+
+use nalgebra::Vector2;
+
+use super::constants::SIZE_GRID;
+
+/// Side of the square chunks activity is tracked in.
+pub const CHUNK_SIZE: usize = 10;
+
+const CHUNKS: [usize; 2] = [
+    SIZE_GRID[0].div_ceil(CHUNK_SIZE),
+    SIZE_GRID[1].div_ceil(CHUNK_SIZE),
+];
+
+/// Chunks of the grid that may change in the next generation of an automaton
+/// whose slots depend only on themselves and their eight neighbours.
+///
+/// A slot can only change if it or a neighbour changed since the previous
+/// generation, so only the chunks [`EditedChunks`] marked around changes are
+/// active, whether the changes were made by the automaton or by editing the grid.
+///
+/// Only the automaton modes are scheduled this way. The ecosystem keeps sweeping
+/// the whole grid: its nutrients, minerals and signals diffuse into every open
+/// slot, so no part of a world with any of them ever stays still. Its edits are
+/// still marked, so an automaton taking over afterwards wakes where they happened.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActiveChunks {
+    active: Vec<bool>,
+    /// Rule the last generation ran with.
+    rule: u64,
+}
+
+/// Chunks around the slots changed since they were last picked up, by an
+/// automaton or by the light map. They are marked as the changes are made, so
+/// that picking them up costs nothing for the rest of the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditedChunks {
+    marked: Vec<bool>,
+    chunks: Vec<usize>,
+}
+
+impl ActiveChunks {
+    /// Activates the chunks edited since the last generation, or every chunk if
+    /// `rule` differs from the last one.
+    pub fn prepare(&mut self, rule: u64, edited: &mut EditedChunks) {
+        if self.active.is_empty() || rule != self.rule {
+            self.rule = rule;
+            self.active = vec![true; CHUNKS[0] * CHUNKS[1]];
+            edited.clear();
+            return;
+        }
+
+        self.active.fill(false);
+        for chunk in edited.chunks.drain(..) {
+            edited.marked[chunk] = false;
+            self.active[chunk] = true;
+        }
+    }
+
+    /// Active slots of a band of columns, column by column.
+    pub fn slots(&self, band: Range<usize>) -> impl Iterator<Item = Vector2<usize>> + '_ {
+        band.flat_map(move |x| {
+            (0..CHUNKS[1])
+                .filter(move |&chunk| self.active[x / CHUNK_SIZE * CHUNKS[1] + chunk])
+                .flat_map(move |chunk| {
+                    let end = ((chunk + 1) * CHUNK_SIZE).min(SIZE_GRID[1]);
+                    (chunk * CHUNK_SIZE..end).map(move |y| Vector2::new(x, y))
+                })
+        })
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.iter().filter(|&&active| active).count()
+    }
+}
+
+impl Default for EditedChunks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EditedChunks {
+    pub fn new() -> Self {
+        Self {
+            marked: vec![false; CHUNKS[0] * CHUNKS[1]],
+            chunks: vec![],
+        }
+    }
+
+    /// Marks the chunks of the slot and of its neighbours, which all read it.
+    pub fn mark(&mut self, pos: Vector2<usize>) {
+        let xs = pos.x.saturating_sub(1)..=(pos.x + 1).min(SIZE_GRID[0] - 1);
+        for x in xs {
+            let ys = pos.y.saturating_sub(1)..=(pos.y + 1).min(SIZE_GRID[1] - 1);
+            for y in ys {
+                let chunk = x / CHUNK_SIZE * CHUNKS[1] + y / CHUNK_SIZE;
+                if !self.marked[chunk] {
+                    self.marked[chunk] = true;
+                    self.chunks.push(chunk);
+                }
+            }
+        }
+    }
+
+    /// Marks every chunk, for a consumer that has not seen the grid yet.
+    pub fn mark_all(&mut self) {
+        self.clear();
+        self.marked.fill(true);
+        self.chunks.extend(0..CHUNKS[0] * CHUNKS[1]);
+    }
+
+    /// Slots of the marked chunks, chunk by chunk, unmarking them.
+    pub fn take_slots(&mut self) -> impl Iterator<Item = Vector2<usize>> {
+        let chunks = std::mem::take(&mut self.chunks);
+        for &chunk in &chunks {
+            self.marked[chunk] = false;
+        }
+
+        chunks.into_iter().flat_map(|chunk| {
+            let (cx, cy) = (chunk / CHUNKS[1], chunk % CHUNKS[1]);
+            let xs = cx * CHUNK_SIZE..((cx + 1) * CHUNK_SIZE).min(SIZE_GRID[0]);
+            xs.flat_map(move |x| {
+                let ys = cy * CHUNK_SIZE..((cy + 1) * CHUNK_SIZE).min(SIZE_GRID[1]);
+                ys.map(move |y| Vector2::new(x, y))
+            })
+        })
+    }
+
+    fn clear(&mut self) {
+        for chunk in self.chunks.drain(..) {
+            self.marked[chunk] = false;
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::ops::{Index, IndexMut};
+
+use nalgebra::Vector2;
+
+use super::constants::SIZE_GRID;
+
+/// Largest diffusion rate for which the explicit scheme of [`Field::diffuse`] stays stable.
+pub const MAX_DIFFUSION_RATE: f32 = 0.25;
+
+/// Scalar value per grid slot, with the same dimensions as [`super::layout::Layout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    values: Vec<f32>,
+}
+
+impl Field {
+    pub fn new() -> Self {
+        Self {
+            values: vec![0.0; SIZE_GRID[0] * SIZE_GRID[1]],
+        }
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.values.iter().map(|&v| v as f64).sum()
+    }
+
+    pub fn scale(&mut self, factor: f32) {
+        for value in self.values.iter_mut() {
+            *value *= factor;
+        }
+    }
+
+    /// One explicit diffusion step.
+    ///
+    /// Every pair of adjacent open slots exchanges `rate * difference`, so the
+    /// total over the grid is conserved. Closed slots and the grid edges are
+    /// no-flux boundaries. `rate` is clamped to [`MAX_DIFFUSION_RATE`], which
+    /// keeps every value non-negative.
+    pub fn diffuse<F>(&mut self, rate: f32, is_open: F)
+    where
+        F: Fn(Vector2<usize>) -> bool,
+    {
+        let rate = rate.clamp(0.0, MAX_DIFFUSION_RATE);
+        if rate == 0.0 {
+            return;
+        }
+
+        let mut next = self.values.clone();
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                if !is_open(pos) {
+                    continue;
+                }
+
+                for other in [Vector2::new(x + 1, y), Vector2::new(x, y + 1)] {
+                    if other.x >= SIZE_GRID[0] || other.y >= SIZE_GRID[1] || !is_open(other) {
+                        continue;
+                    }
+
+                    let flux = rate * (self[pos] - self[other]);
+                    next[Self::offset(pos)] -= flux;
+                    next[Self::offset(other)] += flux;
+                }
+            }
+        }
+
+        self.values = next;
+    }
+
+    fn offset(pos: Vector2<usize>) -> usize {
+        pos.x * SIZE_GRID[1] + pos.y
+    }
+}
+
+impl Index<Vector2<usize>> for Field {
+    type Output = f32;
+    fn index(&self, index: Vector2<usize>) -> &Self::Output {
+        &self.values[Self::offset(index)]
+    }
+}
+
+impl IndexMut<Vector2<usize>> for Field {
+    fn index_mut(&mut self, index: Vector2<usize>) -> &mut Self::Output {
+        &mut self.values[Self::offset(index)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded() -> Field {
+        let mut field = Field::new();
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                field[Vector2::new(x, y)] = ((x * 31 + y * 17) % 23) as f32;
+            }
+        }
+        field[Vector2::new(50, 50)] = 1000.0;
+
+        field
+    }
+
+    #[test]
+    fn diffusion_conserves_total() {
+        let mut field = seeded();
+        let total = field.sum();
+
+        for _ in 0..100 {
+            field.diffuse(MAX_DIFFUSION_RATE, |_| true);
+        }
+
+        assert!((field.sum() - total).abs() / total < 1e-5);
+    }
+
+    #[test]
+    fn diffusion_is_stable_for_too_large_rates() {
+        let mut field = seeded();
+        let max = field.values.iter().cloned().fold(0.0, f32::max);
+
+        for _ in 0..100 {
+            field.diffuse(10.0, |_| true);
+        }
+
+        assert!(field.values.iter().all(|&v| v >= 0.0 && v <= max));
+    }
+
+    #[test]
+    fn walls_are_no_flux_boundaries() {
+        let mut field = Field::new();
+        field[Vector2::new(10, 10)] = 100.0;
+        let is_open = |pos: Vector2<usize>| pos.x != 20;
+
+        for _ in 0..500 {
+            field.diffuse(MAX_DIFFUSION_RATE, is_open);
+        }
+
+        let behind_wall: f64 = (20..SIZE_GRID[0])
+            .flat_map(|x| (0..SIZE_GRID[1]).map(move |y| Vector2::new(x, y)))
+            .map(|pos| field[pos] as f64)
+            .sum();
+        assert_eq!(behind_wall, 0.0);
+        assert!((field.sum() - 100.0).abs() < 1e-3);
+    }
+}
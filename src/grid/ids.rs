@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector2;
+
+use crate::cell::CellId;
+
+/// Identifiers of the cells on the grid and the slots they are in.
+///
+/// Identifiers are issued in increasing order, starting from `#1`, and are
+/// never issued again after their cell dies.
+#[derive(Debug, Clone)]
+pub struct CellIds {
+    next: u64,
+    positions: HashMap<CellId, Vector2<usize>>,
+}
+
+impl CellIds {
+    pub fn new() -> Self {
+        Self {
+            next: 1,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Slot of the living cell with the identifier.
+    pub fn position(&self, id: CellId) -> Option<Vector2<usize>> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Number of living cells.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Identifier the next cell will get.
+    pub fn next(&self) -> CellId {
+        CellId(self.next)
+    }
+
+    pub(super) fn issue(&mut self, pos: Vector2<usize>) -> CellId {
+        let id = CellId(self.next);
+        self.next += 1;
+        self.positions.insert(id, pos);
+        id
+    }
+
+    pub(super) fn track(&mut self, id: CellId, pos: Vector2<usize>) {
+        if id.is_assigned() {
+            self.positions.insert(id, pos);
+        }
+    }
+
+    pub(super) fn forget(&mut self, id: CellId) {
+        self.positions.remove(&id);
+    }
+}
@@ -3,21 +3,36 @@ use std::ops::{Index, IndexMut};
 use super::constants::SIZE_GRID;
 
 pub struct Layout<T> {
-    inner: [[Option<T>; SIZE_GRID[0]]; SIZE_GRID[1]],
+    // Boxed: a layout of cells is too large to be kept on the stack.
+    inner: Box<[[Option<T>; SIZE_GRID[0]]; SIZE_GRID[1]]>,
 }
 
 impl<T> Layout<T> {
     pub fn new() -> Self {
+        let rows: Box<[_]> = (0..SIZE_GRID[1])
+            .map(|_| [const { None }; SIZE_GRID[0]])
+            .collect();
+
         Self {
-            inner: [const { [const { None }; SIZE_GRID[0]] }; SIZE_GRID[1]],
+            inner: rows.try_into().unwrap_or_else(|_| unreachable!()),
         }
     }
 
+    pub fn contains(pos: nalgebra::Vector2<usize>) -> bool {
+        pos.x < SIZE_GRID[0] && pos.y < SIZE_GRID[1]
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, [Option<T>; SIZE_GRID[0]]> {
         self.inner.iter()
     }
 }
 
+impl<T> Default for Layout<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Index<nalgebra::Vector2<usize>> for Layout<T> {
     type Output = Option<T>;
     fn index(&self, index: nalgebra::Vector2<usize>) -> &Self::Output {
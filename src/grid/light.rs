@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use nalgebra::Vector2;
+
+use crate::zone::{Zone, ZoneKind};
+
+use super::{
+    chunks::EditedChunks, constants::SIZE_GRID, field::Field, layout::Layout, store::CellStore,
+};
+
+/// Distance in slots at which the light of a source fades out completely.
+pub const LIGHT_RADIUS: usize = 12;
+/// Fraction of light passing through a slot occupied by a cell.
+pub const CELL_TRANSMITTANCE: f32 = 0.5;
+
+const PATCH_SIZE: usize = 2 * LIGHT_RADIUS + 1;
+
+/// Light emitted by light-source zones, shaded by walls and cells.
+///
+/// The contribution of every source is kept, so [`LightMap::update`] only
+/// recomputes the sources whose surroundings changed since the last update. The
+/// changes are found among the slots marked with [`LightMap::mark`] as cells and
+/// zones are edited; a new map looks at every slot once.
+#[derive(Debug, Clone)]
+pub struct LightMap {
+    light: Field,
+    emission: Field,
+    transmittance: Field,
+    sources: BTreeMap<(usize, usize), Vec<f32>>,
+    edited: EditedChunks,
+}
+
+impl LightMap {
+    pub fn new() -> Self {
+        let mut transmittance = Field::new();
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                transmittance[Vector2::new(x, y)] = 1.0;
+            }
+        }
+
+        let mut edited = EditedChunks::new();
+        edited.mark_all();
+
+        Self {
+            light: Field::new(),
+            emission: Field::new(),
+            transmittance,
+            sources: BTreeMap::new(),
+            edited,
+        }
+    }
+
+    /// Notes that the zone or the cell of the slot changed.
+    pub fn mark(&mut self, pos: Vector2<usize>) {
+        self.edited.mark(pos);
+    }
+
+    /// Light emitted by the sources that reaches the slot.
+    pub fn light(&self, pos: Vector2<usize>) -> f32 {
+        self.light[pos].max(0.0)
+    }
+
+    pub fn update(&mut self, zones: &Layout<Zone>, cells: &CellStore) {
+        let mut dirty = vec![];
+        for pos in self.edited.take_slots() {
+            let emission = Self::emission_of(zones[pos]);
+            if emission != self.emission[pos] {
+                self.emission[pos] = emission;
+                dirty.push((pos.x, pos.y));
+            }
+
+            let transmittance = Self::transmittance_of(zones[pos], cells.contains(pos));
+            if transmittance != self.transmittance[pos] {
+                self.transmittance[pos] = transmittance;
+                dirty.extend(self.sources_around(pos));
+            }
+        }
+
+        dirty.sort_unstable();
+        dirty.dedup();
+        for source in dirty {
+            self.recompute_source(source);
+        }
+    }
+
+    fn emission_of(zone: Option<Zone>) -> f32 {
+        match zone {
+            Some(zone) if zone.kind == ZoneKind::LightSource => zone.environment.light,
+            _ => 0.0,
+        }
+    }
+
+    fn transmittance_of(zone: Option<Zone>, has_cell: bool) -> f32 {
+        if zone.is_some_and(|zone| zone.blocks_cells()) {
+            0.0
+        } else if has_cell {
+            CELL_TRANSMITTANCE
+        } else {
+            1.0
+        }
+    }
+
+    fn sources_around(&self, pos: Vector2<usize>) -> Vec<(usize, usize)> {
+        let mut sources = vec![];
+        for x in pos.x.saturating_sub(LIGHT_RADIUS)..(pos.x + LIGHT_RADIUS + 1).min(SIZE_GRID[0]) {
+            for y in
+                pos.y.saturating_sub(LIGHT_RADIUS)..(pos.y + LIGHT_RADIUS + 1).min(SIZE_GRID[1])
+            {
+                if self.emission[Vector2::new(x, y)] > 0.0 {
+                    sources.push((x, y));
+                }
+            }
+        }
+
+        sources
+    }
+
+    fn recompute_source(&mut self, source: (usize, usize)) {
+        if let Some(patch) = self.sources.remove(&source) {
+            self.apply_patch(source, &patch, -1.0);
+        }
+
+        let intensity = self.emission[Vector2::new(source.0, source.1)];
+        if intensity <= 0.0 {
+            return;
+        }
+
+        let patch = self.compute_patch(source, intensity);
+        self.apply_patch(source, &patch, 1.0);
+        self.sources.insert(source, patch);
+    }
+
+    fn compute_patch(&self, source: (usize, usize), intensity: f32) -> Vec<f32> {
+        let mut patch = vec![0.0; PATCH_SIZE * PATCH_SIZE];
+        for (i, value) in patch.iter_mut().enumerate() {
+            let Some(target) = Self::patch_target(source, i) else {
+                continue;
+            };
+
+            let dx = target.0 as f32 - source.0 as f32;
+            let dy = target.1 as f32 - source.1 as f32;
+            let falloff = 1.0 - (dx * dx + dy * dy).sqrt() / LIGHT_RADIUS as f32;
+            if falloff <= 0.0 {
+                continue;
+            }
+
+            *value = intensity * falloff * falloff * self.occlusion(source, target);
+        }
+
+        patch
+    }
+
+    fn apply_patch(&mut self, source: (usize, usize), patch: &[f32], sign: f32) {
+        for (i, value) in patch.iter().enumerate() {
+            if let Some(target) = Self::patch_target(source, i) {
+                self.light[Vector2::new(target.0, target.1)] += sign * value;
+            }
+        }
+    }
+
+    fn patch_target(source: (usize, usize), i: usize) -> Option<(usize, usize)> {
+        let x = (source.0 + i / PATCH_SIZE).checked_sub(LIGHT_RADIUS)?;
+        let y = (source.1 + i % PATCH_SIZE).checked_sub(LIGHT_RADIUS)?;
+        (x < SIZE_GRID[0] && y < SIZE_GRID[1]).then_some((x, y))
+    }
+
+    /// Product of the transmittance of the slots strictly between `from` and `to`.
+    fn occlusion(&self, from: (usize, usize), to: (usize, usize)) -> f32 {
+        let (x0, y0) = (from.0 as isize, from.1 as isize);
+        let (x1, y1) = (to.0 as isize, to.1 as isize);
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+
+        let mut transmittance = 1.0;
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        loop {
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+
+            if (x, y) == (x1, y1) || (x, y) == (x0, y0) {
+                break;
+            }
+
+            transmittance *= self.transmittance[Vector2::new(x as usize, y as usize)];
+            if transmittance == 0.0 {
+                break;
+            }
+        }
+
+        transmittance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+
+    #[test]
+    fn updates_match_a_full_recompute() {
+        let mut zones = Layout::new();
+        let mut cells = CellStore::new(SIZE_GRID[0], SIZE_GRID[1]);
+        let mut light = LightMap::new();
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |n: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as usize
+        };
+
+        for update in 0..200 {
+            for _ in 0..20 {
+                let pos = Vector2::new(next(SIZE_GRID[0]), next(SIZE_GRID[1]));
+                light.mark(pos);
+                match next(5) {
+                    0 => zones[pos] = Some(Zone::new(ZoneKind::LightSource)),
+                    1 => zones[pos] = Some(Zone::new(ZoneKind::Wall)),
+                    2 => zones[pos] = None,
+                    3 => {
+                        cells.insert(pos, Cell::default());
+                    }
+                    _ => {
+                        cells.remove(pos);
+                    }
+                }
+            }
+            light.update(&zones, &cells);
+
+            if update % 40 == 39 {
+                let mut fresh = LightMap::new();
+                fresh.update(&zones, &cells);
+                for x in 0..SIZE_GRID[0] {
+                    for y in 0..SIZE_GRID[1] {
+                        let pos = Vector2::new(x, y);
+                        let drift = (light.light[pos] - fresh.light[pos]).abs();
+                        assert!(drift < 1e-4, "{drift} at {pos:?} after {update} updates");
+                    }
+                }
+            }
+        }
+    }
+}
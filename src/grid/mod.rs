@@ -1,31 +1,174 @@
 use std::ptr::null;
 
+use nalgebra::Vector2;
+
+use bonds::Bonds;
+use chunks::EditedChunks;
 use constants::{SIZE_GRID, SIZE_RENDER_CELL_GRID};
+use field::Field;
+use ids::CellIds;
 use layout::Layout;
+use light::LightMap;
+use signals::Signals;
+use store::CellStore;
 
 use crate::{
     cell::Cell,
     control::Camera,
     opengl::prelude::{get_location, GetId, Program, Shader, Vao, Vbo},
-    zone::Zone,
+    zone::{Environment, Zone, ZoneKind},
 };
 
+pub mod bands;
+pub mod bonds;
+pub mod chunks;
 pub mod constants;
+pub mod field;
+pub mod ids;
 pub mod layout;
+pub mod light;
+pub mod signals;
+pub mod store;
 
 pub struct Grid {
     pub layout_zones: Layout<Zone>,
-    pub layout_cells: Layout<Cell>,
+    pub cells: CellStore,
+    /// Slots of the cells by their identifiers.
+    pub cell_ids: CellIds,
+    /// Chunks around the slots whose cells or zones changed, for the automata.
+    pub edited: EditedChunks,
+    pub bonds: Bonds,
+    /// Organic food lying in every slot.
+    pub nutrients: Field,
+    pub minerals: Field,
+    /// Mass of dead matter lying in every slot.
+    pub remains: Field,
+    pub light: LightMap,
+    pub signals: Signals,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Grid {
     pub fn new() -> Self {
         Self {
             layout_zones: Layout::new(),
-            layout_cells: Layout::new(),
+            cells: CellStore::new(SIZE_GRID[0], SIZE_GRID[1]),
+            cell_ids: CellIds::new(),
+            edited: EditedChunks::new(),
+            bonds: Bonds::new(),
+            nutrients: Field::new(),
+            minerals: Field::new(),
+            remains: Field::new(),
+            light: LightMap::new(),
+            signals: Signals::new(),
         }
     }
 
+    /// Puts a new cell into the slot under a fresh identifier, replacing the
+    /// cell already there.
+    pub fn spawn_cell(&mut self, pos: Vector2<usize>, mut cell: Cell) {
+        self.remove_cell(pos);
+        cell.id = self.cell_ids.issue(pos);
+        self.cells.insert(pos, cell);
+        self.mark_edited(pos);
+    }
+
+    /// Takes the cell out of the slot, breaking its bonds.
+    pub fn remove_cell(&mut self, pos: Vector2<usize>) -> Option<Cell> {
+        self.bonds.unbond_all(pos);
+        let cell = self.cells.remove(pos)?;
+        self.cell_ids.forget(cell.id);
+        self.mark_edited(pos);
+        Some(cell)
+    }
+
+    /// Puts the zone into the slot, taking out the cell if the zone keeps cells out.
+    pub fn set_zone(&mut self, pos: Vector2<usize>, zone: Option<Zone>) {
+        self.layout_zones[pos] = zone;
+        if zone.is_some_and(|zone| zone.blocks_cells()) {
+            self.remove_cell(pos);
+        }
+        self.mark_edited(pos);
+    }
+
+    /// Moves the cells of `from` to `to` together with their bonds and
+    /// identifiers; the destinations must be empty or among the sources.
+    pub fn move_cells(&mut self, from: &[Vector2<usize>], to: &[Vector2<usize>]) {
+        let cells: Vec<_> = from.iter().map(|&pos| self.cells.remove(pos)).collect();
+        for &pos in from.iter().chain(to) {
+            self.mark_edited(pos);
+        }
+        self.bonds.relocate(from, to);
+        for (&pos, cell) in to.iter().zip(cells) {
+            if let Some(cell) = cell {
+                self.cell_ids.track(cell.id, pos);
+                self.cells.insert(pos, cell);
+            }
+        }
+    }
+
+    /// Notes a change of the cell or zone of the slot for the automata and the light.
+    fn mark_edited(&mut self, pos: Vector2<usize>) {
+        self.edited.mark(pos);
+        self.light.mark(pos);
+    }
+
+    /// Environment of the slot: the parameters of its zone, or the defaults if it has none.
+    pub fn environment(&self, pos: Vector2<usize>) -> Environment {
+        self.layout_zones[pos]
+            .map(|zone| zone.environment)
+            .unwrap_or_default()
+    }
+
+    /// Light reaching the slot: the ambient light of its zone plus the light of nearby sources.
+    pub fn light(&self, pos: Vector2<usize>) -> f32 {
+        let ambient = match self.layout_zones[pos] {
+            Some(zone) if zone.kind == ZoneKind::LightSource => 0.0,
+            Some(zone) => zone.environment.light,
+            None => Environment::default().light,
+        };
+
+        ambient + self.light.light(pos)
+    }
+
+    /// Whether the zone of the slot keeps cells out of it.
+    pub fn is_blocked(&self, pos: Vector2<usize>) -> bool {
+        self.layout_zones[pos].is_some_and(|zone| zone.blocks_cells())
+    }
+
+    /// `pos` shifted by `dx` and `dy`, if the result lies inside the grid.
+    pub fn shifted(pos: Vector2<usize>, dx: isize, dy: isize) -> Option<Vector2<usize>> {
+        let x = pos.x.checked_add_signed(dx)?;
+        let y = pos.y.checked_add_signed(dy)?;
+        let pos = Vector2::new(x, y);
+        Layout::<Cell>::contains(pos).then_some(pos)
+    }
+
+    /// Positions of the four orthogonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours(pos: Vector2<usize>) -> impl Iterator<Item = Vector2<usize>> {
+        let (x, y) = (pos.x as isize, pos.y as isize);
+        [(x - 1, y), (x, y + 1), (x + 1, y), (x, y - 1)]
+            .into_iter()
+            .filter(|&(x, y)| x >= 0 && y >= 0)
+            .map(|(x, y)| Vector2::new(x as usize, y as usize))
+            .filter(|&pos| Layout::<Cell>::contains(pos))
+    }
+
+    pub fn occupied_neighbours(&self, pos: Vector2<usize>) -> Vec<Vector2<usize>> {
+        Self::neighbours(pos)
+            .filter(|&n| self.cells.contains(n))
+            .collect()
+    }
+
+    pub fn cell_positions(&self) -> Vec<Vector2<usize>> {
+        self.cells.positions().collect()
+    }
+
     pub fn create_render_info(&self) -> (Vao, Vbo) {
         let mut vao @ mut vbo = 0;
         let vertices = [
@@ -0,0 +1,62 @@
+use nalgebra::Vector2;
+
+use super::field::Field;
+
+/// Number of signal chemicals cells can emit and sense.
+pub const SIGNAL_CHANNELS: usize = 4;
+
+/// How the chemical of a channel spreads and fades.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalChannel {
+    /// Rate at which the chemical spreads between neighbouring slots per tick.
+    pub diffusion: f32,
+    /// Fraction of the chemical breaking down per tick.
+    pub decay: f32,
+}
+
+impl Default for SignalChannel {
+    fn default() -> Self {
+        Self {
+            diffusion: 0.2,
+            decay: 0.05,
+        }
+    }
+}
+
+/// Concentrations of the signal chemicals (morphogens), one field per channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signals {
+    channels: [Field; SIGNAL_CHANNELS],
+}
+
+impl Signals {
+    pub fn new() -> Self {
+        Self {
+            channels: std::array::from_fn(|_| Field::new()),
+        }
+    }
+
+    pub fn channel(&self, channel: usize) -> &Field {
+        &self.channels[channel]
+    }
+
+    /// Concentrations of every channel in the slot.
+    pub fn sample(&self, pos: Vector2<usize>) -> [f32; SIGNAL_CHANNELS] {
+        std::array::from_fn(|channel| self.channels[channel][pos])
+    }
+
+    pub fn emit(&mut self, pos: Vector2<usize>, channel: usize, amount: f32) {
+        self.channels[channel][pos] += amount;
+    }
+
+    /// Lets every channel decay and then diffuse through the open slots.
+    pub fn update<F>(&mut self, params: &[SignalChannel; SIGNAL_CHANNELS], is_open: F)
+    where
+        F: Fn(Vector2<usize>) -> bool,
+    {
+        for (field, params) in self.channels.iter_mut().zip(params) {
+            field.scale(1.0 - params.decay.clamp(0.0, 1.0));
+            field.diffuse(params.diffusion, &is_open);
+        }
+    }
+}
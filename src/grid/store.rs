@@ -0,0 +1,317 @@
+use nalgebra::Vector2;
+
+use crate::cell::{Brain, Cell, CellId, CellType, Genome, Plasmid};
+
+/// Cells of a grid stored column by column: an occupancy bitset and one dense
+/// array per field, indexed by slot.
+///
+/// Sweeping a field, such as the energy, touches only that field instead of
+/// whole cells and the tags of empty slots. Single cells are reached through
+/// the views [`CellRef`] and [`CellMut`]. Entries of empty slots hold default
+/// values.
+#[derive(Debug, Clone)]
+pub struct CellStore {
+    width: usize,
+    height: usize,
+    /// One bit per slot, in the order of the columns.
+    occupied: Vec<u64>,
+    population: usize,
+    columns: Columns,
+}
+
+/// Dense arrays of the fields of the cells, one entry per slot.
+#[derive(Debug, Clone, Default)]
+pub struct Columns {
+    pub id: Vec<CellId>,
+    pub mass: Vec<f32>,
+    pub energy: Vec<f32>,
+    pub age: Vec<u64>,
+    pub color: Vec<[f32; 3]>,
+    pub cell_type: Vec<CellType>,
+    pub state: Vec<u8>,
+    pub genome: Vec<Genome>,
+    pub plasmids: Vec<Vec<Plasmid>>,
+    pub brain: Vec<Option<Brain>>,
+}
+
+/// Stored cell: copies of its small fields and borrows of the others.
+#[derive(Debug, Clone, Copy)]
+pub struct CellRef<'a> {
+    pub id: CellId,
+    pub mass: f32,
+    pub energy: f32,
+    /// Ticks lived.
+    pub age: u64,
+    pub color: [f32; 3],
+    pub cell_type: CellType,
+    pub state: u8,
+    pub genome: &'a Genome,
+    pub plasmids: &'a [Plasmid],
+    pub brain: Option<&'a Brain>,
+}
+
+/// Mutable fields of a stored cell; its identifier is not one of them.
+#[derive(Debug)]
+pub struct CellMut<'a> {
+    pub mass: &'a mut f32,
+    pub energy: &'a mut f32,
+    pub age: &'a mut u64,
+    pub color: &'a mut [f32; 3],
+    pub cell_type: &'a mut CellType,
+    pub state: &'a mut u8,
+    pub genome: &'a mut Genome,
+    pub plasmids: &'a mut Vec<Plasmid>,
+    pub brain: &'a mut Option<Brain>,
+}
+
+impl CellRef<'_> {
+    /// Copy of the cell.
+    pub fn to_cell(self) -> Cell {
+        Cell {
+            id: self.id,
+            mass: self.mass,
+            energy: self.energy,
+            age: self.age,
+            color: self.color,
+            cell_type: self.cell_type,
+            state: self.state,
+            genome: self.genome.clone(),
+            plasmids: self.plasmids.to_vec(),
+            brain: self.brain.cloned(),
+        }
+    }
+}
+
+impl CellStore {
+    pub fn new(width: usize, height: usize) -> Self {
+        let slots = width * height;
+        Self {
+            width,
+            height,
+            occupied: vec![0; slots.div_ceil(64)],
+            population: 0,
+            columns: Columns {
+                id: vec![CellId::default(); slots],
+                mass: vec![0.0; slots],
+                energy: vec![0.0; slots],
+                age: vec![0; slots],
+                color: vec![[0.0; 3]; slots],
+                cell_type: vec![CellType::default(); slots],
+                state: vec![0; slots],
+                genome: vec![Genome::default(); slots],
+                plasmids: vec![vec![]; slots],
+                brain: vec![None; slots],
+            },
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of stored cells.
+    pub fn len(&self) -> usize {
+        self.population
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.population == 0
+    }
+
+    /// Whether a cell stands in the slot.
+    pub fn contains(&self, pos: Vector2<usize>) -> bool {
+        self.index(pos)
+            .is_some_and(|i| self.occupied[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Puts the cell in the slot, returning the cell it replaces.
+    ///
+    /// # Panics
+    ///
+    /// If the slot lies outside the store.
+    pub fn insert(&mut self, pos: Vector2<usize>, cell: Cell) -> Option<Cell> {
+        let previous = self.remove(pos);
+        let i = self.index(pos).expect("slot outside the store");
+
+        let c = &mut self.columns;
+        c.id[i] = cell.id;
+        c.mass[i] = cell.mass;
+        c.energy[i] = cell.energy;
+        c.age[i] = cell.age;
+        c.color[i] = cell.color;
+        c.cell_type[i] = cell.cell_type;
+        c.state[i] = cell.state;
+        c.genome[i] = cell.genome;
+        c.plasmids[i] = cell.plasmids;
+        c.brain[i] = cell.brain;
+
+        self.occupied[i / 64] |= 1 << (i % 64);
+        self.population += 1;
+        previous
+    }
+
+    /// Takes the cell out of the slot.
+    pub fn remove(&mut self, pos: Vector2<usize>) -> Option<Cell> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let i = self.index(pos)?;
+        self.occupied[i / 64] &= !(1 << (i % 64));
+        self.population -= 1;
+
+        let c = &mut self.columns;
+        Some(Cell {
+            id: std::mem::take(&mut c.id[i]),
+            mass: std::mem::take(&mut c.mass[i]),
+            energy: std::mem::take(&mut c.energy[i]),
+            age: std::mem::take(&mut c.age[i]),
+            color: std::mem::take(&mut c.color[i]),
+            cell_type: std::mem::take(&mut c.cell_type[i]),
+            state: std::mem::take(&mut c.state[i]),
+            genome: std::mem::take(&mut c.genome[i]),
+            plasmids: std::mem::take(&mut c.plasmids[i]),
+            brain: std::mem::take(&mut c.brain[i]),
+        })
+    }
+
+    pub fn get(&self, pos: Vector2<usize>) -> Option<CellRef<'_>> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let i = self.index(pos)?;
+
+        let c = &self.columns;
+        Some(CellRef {
+            id: c.id[i],
+            mass: c.mass[i],
+            energy: c.energy[i],
+            age: c.age[i],
+            color: c.color[i],
+            cell_type: c.cell_type[i],
+            state: c.state[i],
+            genome: &c.genome[i],
+            plasmids: &c.plasmids[i],
+            brain: c.brain[i].as_ref(),
+        })
+    }
+
+    pub fn get_mut(&mut self, pos: Vector2<usize>) -> Option<CellMut<'_>> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let i = self.index(pos)?;
+
+        let c = &mut self.columns;
+        Some(CellMut {
+            mass: &mut c.mass[i],
+            energy: &mut c.energy[i],
+            age: &mut c.age[i],
+            color: &mut c.color[i],
+            cell_type: &mut c.cell_type[i],
+            state: &mut c.state[i],
+            genome: &mut c.genome[i],
+            plasmids: &mut c.plasmids[i],
+            brain: &mut c.brain[i],
+        })
+    }
+
+    /// Occupied slots, column by column.
+    pub fn positions(&self) -> impl Iterator<Item = Vector2<usize>> + '_ {
+        self.occupied
+            .iter()
+            .enumerate()
+            .flat_map(|(word, &bits)| {
+                let mut bits = bits;
+                std::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    Some(word * 64 + bit)
+                })
+            })
+            .map(|i| Vector2::new(i / self.height, i % self.height))
+    }
+
+    /// Dense arrays of the fields, for sweeps over every cell. Entries of empty
+    /// slots are ignored by the per-cell accessors.
+    pub fn columns(&self) -> &Columns {
+        &self.columns
+    }
+
+    /// Mutable dense arrays of the fields. Their lengths must be kept.
+    pub fn columns_mut(&mut self) -> &mut Columns {
+        &mut self.columns
+    }
+
+    /// Slot index of the position, if it lies inside the store.
+    pub fn index(&self, pos: Vector2<usize>) -> Option<usize> {
+        (pos.x < self.width && pos.y < self.height).then(|| pos.x * self.height + pos.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(i: usize) -> Cell {
+        Cell {
+            energy: i as f32,
+            mass: 1.0 + (i % 7) as f32,
+            color: [(i % 3) as f32 / 2.0, 0.5, 1.0],
+            plasmids: vec![Plasmid::default(); i % 3],
+            ..Cell::default()
+        }
+    }
+
+    #[test]
+    fn keeps_the_cells_put_in() {
+        let (width, height) = (90, 70);
+        let mut store = CellStore::new(width, height);
+        let mut expected = vec![];
+        for x in 0..width {
+            for y in 0..height {
+                if (x * 7 + y * 13) % 3 == 1 {
+                    let pos = Vector2::new(x, y);
+                    store.insert(pos, cell(x * height + y));
+                    expected.push(pos);
+                }
+            }
+        }
+
+        assert_eq!(store.len(), expected.len());
+        assert_eq!(store.positions().collect::<Vec<_>>(), expected);
+        for &pos in &expected {
+            let copy = store.get(pos).map(|cell| cell.to_cell());
+            let removed = store.remove(pos);
+            assert_eq!(format!("{copy:?}"), format!("{removed:?}"));
+            assert_eq!(
+                format!("{removed:?}"),
+                format!("{:?}", Some(cell(pos.x * height + pos.y)))
+            );
+        }
+        assert!(store.is_empty() && store.positions().next().is_none());
+    }
+
+    #[test]
+    fn gives_access_to_single_cells() {
+        let mut store = CellStore::new(70, 3);
+        let pos = Vector2::new(65, 2);
+        assert!(store.insert(pos, cell(5)).is_none());
+        assert!(store.contains(pos) && !store.contains(Vector2::new(65, 1)));
+
+        if let Some(cell) = store.get_mut(pos) {
+            *cell.energy += 1.0;
+        }
+        assert_eq!(store.get(pos).map(|cell| cell.energy), Some(6.0));
+        assert_eq!(store.positions().collect::<Vec<_>>(), vec![pos]);
+
+        assert_eq!(store.remove(pos).map(|cell| cell.energy), Some(6.0));
+        assert!(store.is_empty() && store.get(pos).is_none());
+    }
+}
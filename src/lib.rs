@@ -1,7 +1,12 @@
+pub use cell::Cell;
+pub use grid::{constants::SIZE_GRID, layout::Layout, store::CellStore, Grid};
+
+mod automaton;
 mod cell;
 mod control;
 pub mod game;
 mod grid;
 mod opengl;
+mod simulation;
 mod traits;
 mod zone;
@@ -0,0 +1,401 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use nalgebra::Vector2;
+
+use crate::{cell::Action, grid::Grid};
+
+use super::{stats::PredationEvent, Rng, Simulation};
+
+/// Action chosen by a cell, waiting to be performed.
+pub(super) struct Intent {
+    pub pos: Vector2<usize>,
+    pub action: Action,
+    pub rng: Rng,
+    mass: f32,
+    priority: u64,
+}
+
+impl Intent {
+    pub fn new(pos: Vector2<usize>, action: Action, mass: f32, mut rng: Rng) -> Self {
+        Self {
+            pos,
+            action,
+            mass,
+            priority: rng.next_u64(),
+            rng,
+        }
+    }
+
+    /// Heavier cells win conflicts; equal masses are settled by the random priority.
+    fn outranks(&self, other: &Intent) -> bool {
+        self.mass
+            .total_cmp(&other.mass)
+            .then(self.priority.cmp(&other.priority))
+            .is_gt()
+    }
+}
+
+/// When several cells target the same slot, only the top-ranked one keeps its action.
+pub(super) fn resolve_conflicts(intents: &mut [Intent]) {
+    let mut winners = HashMap::new();
+    for (i, intent) in intents.iter().enumerate() {
+        let Some(target) = intent.action.target() else {
+            continue;
+        };
+
+        match winners.entry(target) {
+            Entry::Vacant(entry) => {
+                entry.insert(i);
+            }
+            Entry::Occupied(mut entry) => {
+                if intent.outranks(&intents[*entry.get()]) {
+                    entry.insert(i);
+                }
+            }
+        }
+    }
+
+    for (i, intent) in intents.iter_mut().enumerate() {
+        if intent
+            .action
+            .target()
+            .is_some_and(|target| winners[&target] != i)
+        {
+            intent.action = Action::Idle;
+        }
+    }
+}
+
+impl Simulation {
+    pub(super) fn perform(
+        &mut self,
+        grid: &mut Grid,
+        pos: Vector2<usize>,
+        action: Action,
+        rng: &mut Rng,
+    ) {
+        // The cell may have been eaten or moved earlier in this tick.
+        if !grid.cells.contains(pos) || self.moved.contains(&pos) {
+            return;
+        }
+
+        match action {
+            Action::Idle => {}
+            Action::Divide(target) => self.divide(grid, pos, target, rng),
+            Action::Mate(partner, target) => self.mate(grid, pos, partner, target, rng),
+            Action::Attack(target) => self.attack(grid, pos, target, rng),
+            Action::Scavenge(target) => self.scavenge(grid, pos, target),
+            Action::Move(target) => self.move_body(grid, pos, target),
+            Action::Bond(target) => {
+                if grid.cells.contains(target) {
+                    grid.bonds.bond(pos, target);
+                }
+            }
+            Action::Unbond(target) => grid.bonds.unbond(pos, target),
+            Action::Differentiate(cell_type) => {
+                if let Some(cell) = grid.cells.get_mut(pos) {
+                    *cell.cell_type = cell_type;
+                }
+            }
+        }
+    }
+
+    fn divide(
+        &mut self,
+        grid: &mut Grid,
+        pos: Vector2<usize>,
+        target: Vector2<usize>,
+        rng: &mut Rng,
+    ) {
+        if grid.cells.contains(target) {
+            return;
+        }
+
+        let strength = self.settings.mutation_strength * grid.environment(target).mutation_rate;
+        let light = grid.light(target);
+        let Some(cell) = grid.cells.get(pos) else {
+            return;
+        };
+
+        // Mother and daughter split the energy.
+        let mut daughter = cell.to_cell();
+        daughter.energy /= 2.0;
+        daughter.age = 0;
+        let stays_bonded = rng.next_f32() < cell.genome.adhesion;
+        daughter.cell_type = cell
+            .genome
+            .differentiation
+            .daughter_type(cell.cell_type, light);
+        daughter.mutate(strength, rng);
+        if let Some(mother) = grid.cells.get_mut(pos) {
+            *mother.energy /= 2.0;
+        }
+        grid.spawn_cell(target, daughter);
+        if stays_bonded {
+            grid.bonds.bond(pos, target);
+        }
+        self.statistics.births += 1;
+    }
+
+    /// Places an offspring combining the genomes of both parents by crossover; each
+    /// parent gives it a share of its energy.
+    fn mate(
+        &mut self,
+        grid: &mut Grid,
+        pos: Vector2<usize>,
+        partner: Vector2<usize>,
+        target: Vector2<usize>,
+        rng: &mut Rng,
+    ) {
+        if grid.cells.contains(target) {
+            return;
+        }
+
+        // The partner may have died, moved or been replaced earlier in this tick.
+        let s = &self.settings;
+        let (Some(cell), Some(other)) = (grid.cells.get(pos), grid.cells.get(partner)) else {
+            return;
+        };
+        if !cell.is_compatible(&other, s.mating_kinship, s.mating_distance) {
+            return;
+        }
+
+        let mut offspring = cell.to_cell();
+        offspring.age = 0;
+        offspring.energy = (cell.energy + other.energy) * s.mating_share;
+        offspring.genome = cell.genome.crossover(other.genome, rng);
+        if let (Some(brain), Some(other)) = (cell.brain, other.brain) {
+            offspring.brain = Some(brain.crossover(other, rng));
+        }
+        for (channel, &other) in offspring.color.iter_mut().zip(other.color.iter()) {
+            if rng.next_f32() < 0.5 {
+                *channel = other;
+            }
+        }
+        offspring.cell_type = offspring
+            .genome
+            .differentiation
+            .daughter_type(cell.cell_type, grid.light(target));
+
+        offspring.merge_plasmids(other.plasmids);
+
+        let strength = s.mutation_strength * grid.environment(target).mutation_rate;
+        offspring.mutate(strength, rng);
+
+        for parent in [pos, partner] {
+            if let Some(parent) = grid.cells.get_mut(parent) {
+                *parent.energy *= 1.0 - s.mating_share;
+            }
+        }
+        grid.spawn_cell(target, offspring);
+        self.statistics.births += 1;
+        self.statistics.sexual_births += 1;
+    }
+
+    /// The attacker wins with a chance equal to its share of the total mass of both cells,
+    /// the mass of the victim weighted by the defence of its type.
+    fn attack(
+        &mut self,
+        grid: &mut Grid,
+        pos: Vector2<usize>,
+        target: Vector2<usize>,
+        rng: &mut Rng,
+    ) {
+        let (Some(attacker), Some(victim)) = (grid.cells.get(pos), grid.cells.get(target)) else {
+            return;
+        };
+
+        let s = &self.settings;
+        let defended_mass = victim.mass * victim.cell_type.physiology().defence;
+        let success = rng.next_f32() < attacker.mass / (attacker.mass + defended_mass);
+        let (energy, mass) = if success {
+            (
+                victim.energy * s.predation_efficiency,
+                victim.mass * s.predation_efficiency,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        if success {
+            grid.remains[target] += victim.mass - mass;
+            grid.remove_cell(target);
+            self.statistics.deaths += 1;
+        }
+
+        if let Some(attacker) = grid.cells.get_mut(pos) {
+            *attacker.energy += energy - s.attack_cost;
+            *attacker.mass = (*attacker.mass + mass).min(s.max_mass);
+            if *attacker.energy <= 0.0 {
+                self.kill(grid, pos);
+            }
+        }
+
+        self.statistics.record_predation(PredationEvent {
+            tick: self.tick,
+            attacker: pos,
+            victim: target,
+            success,
+            energy,
+            mass,
+        });
+    }
+
+    fn scavenge(&mut self, grid: &mut Grid, pos: Vector2<usize>, target: Vector2<usize>) {
+        let eaten = grid.remains[target].min(self.settings.scavenge_amount);
+        grid.remains[target] -= eaten;
+
+        if let Some(cell) = grid.cells.get_mut(pos) {
+            *cell.energy += eaten * self.settings.scavenge_gain;
+        }
+    }
+
+    /// Moves the whole bonded body of the cell by the offset from `pos` to `target`.
+    ///
+    /// Every member pays for its own move: the energy spent grows with its mass, the
+    /// movement cost of its destination zone and that of its type.
+    fn move_body(&mut self, grid: &mut Grid, pos: Vector2<usize>, target: Vector2<usize>) {
+        let body = grid.bonds.body(pos);
+        let members: HashSet<_> = body.iter().copied().collect();
+        let (dx, dy) = (
+            target.x as isize - pos.x as isize,
+            target.y as isize - pos.y as isize,
+        );
+
+        let mut destinations = Vec::with_capacity(body.len());
+        for &member in &body {
+            let Some(destination) = Grid::shifted(member, dx, dy) else {
+                return;
+            };
+            let occupied = grid.cells.contains(destination) && !members.contains(&destination);
+            if occupied || grid.is_blocked(destination) {
+                return;
+            }
+            destinations.push(destination);
+        }
+
+        grid.move_cells(&body, &destinations);
+        self.moved.extend(destinations.iter().copied());
+
+        for destination in destinations {
+            let movement_cost = grid.environment(destination).movement_cost;
+            if let Some(cell) = grid.cells.get_mut(destination) {
+                let type_cost = cell.cell_type.physiology().move_cost;
+                *cell.energy -= *cell.mass * self.settings.move_cost * movement_cost * type_cost;
+                if *cell.energy <= 0.0 {
+                    self.kill(grid, destination);
+                }
+            }
+        }
+        self.statistics.moves += 1;
+    }
+
+    /// Removes the cell, leaving its mass behind as dead matter.
+    pub(super) fn kill(&mut self, grid: &mut Grid, pos: Vector2<usize>) {
+        if let Some(cell) = grid.remove_cell(pos) {
+            grid.remains[pos] += cell.mass;
+            self.statistics.deaths += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{plasmid::MAX_PLASMIDS, Cell, CellType, Plasmid};
+
+    fn intent(x: usize, action: Action, mass: f32, priority: u64) -> Intent {
+        Intent {
+            pos: Vector2::new(x, 0),
+            action,
+            rng: Rng::new(0),
+            mass,
+            priority,
+        }
+    }
+
+    fn actions(intents: &[Intent]) -> Vec<Action> {
+        intents.iter().map(|intent| intent.action).collect()
+    }
+
+    #[test]
+    fn conflicts_go_to_the_heaviest_then_the_highest_priority() {
+        let contested = Vector2::new(10, 10);
+        let tied = Vector2::new(20, 20);
+        let alone = Vector2::new(30, 30);
+        let intents = || {
+            vec![
+                // Mass outweighs priority.
+                intent(0, Action::Move(contested), 1.0, 9),
+                intent(1, Action::Attack(contested), 2.0, 1),
+                intent(2, Action::Divide(contested), 1.5, 5),
+                // Equal masses fall back to the priority.
+                intent(3, Action::Divide(tied), 1.0, 7),
+                intent(4, Action::Move(tied), 1.0, 8),
+                intent(5, Action::Move(alone), 0.1, 0),
+                intent(6, Action::Differentiate(CellType::Leaf), 5.0, 9),
+            ]
+        };
+        let mut expected = vec![
+            Action::Idle,
+            Action::Attack(contested),
+            Action::Idle,
+            Action::Idle,
+            Action::Move(tied),
+            Action::Move(alone),
+            Action::Differentiate(CellType::Leaf),
+        ];
+
+        let mut forward = intents();
+        resolve_conflicts(&mut forward);
+        assert_eq!(actions(&forward), expected);
+
+        // The order the intents come in does not matter.
+        let mut backward = intents();
+        backward.reverse();
+        resolve_conflicts(&mut backward);
+        expected.reverse();
+        assert_eq!(actions(&backward), expected);
+    }
+
+    #[test]
+    fn offspring_carry_the_plasmids_of_both_parents() {
+        let strains = |strains: &[u32]| -> Vec<Plasmid> {
+            strains
+                .iter()
+                .map(|&strain| Plasmid {
+                    strain,
+                    inheritance: 1.0,
+                    ..Plasmid::default()
+                })
+                .collect()
+        };
+        let offspring = |first: &[u32], second: &[u32]| -> Vec<u32> {
+            let mut grid = Grid::new();
+            let (pos, partner, target) =
+                (Vector2::new(5, 5), Vector2::new(6, 5), Vector2::new(5, 6));
+            for (pos, carried) in [(pos, first), (partner, second)] {
+                let cell = Cell {
+                    plasmids: strains(carried),
+                    ..Cell::default()
+                };
+                grid.spawn_cell(pos, cell);
+            }
+
+            let mut simulation = Simulation::new(42);
+            simulation.mate(&mut grid, pos, partner, target, &mut Rng::new(7));
+            let child = grid.cells.get(target).expect("the parents are compatible");
+            child
+                .plasmids
+                .iter()
+                .map(|plasmid| plasmid.strain)
+                .collect()
+        };
+
+        assert_eq!(offspring(&[1], &[2]), [1, 2]);
+        assert_eq!(offspring(&[], &[3]), [3]);
+        // One plasmid per strain, and no more than the cap.
+        assert_eq!(offspring(&[1, 2], &[2, 3]), [1, 2, 3]);
+        assert_eq!(offspring(&[1, 2, 3], &[4, 5, 6]).len(), MAX_PLASMIDS);
+    }
+}
@@ -0,0 +1,188 @@
+use std::f32::consts::TAU;
+
+/// Shape of a periodic modulation, sampled over one period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Curve {
+    Constant,
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+}
+
+impl Curve {
+    pub const ALL: [Curve; 4] = [Curve::Constant, Curve::Sine, Curve::Triangle, Curve::Square];
+
+    /// Value in `0.0..=1.0` at `phase` in `0.0..1.0`; the minimum is at the start of the period.
+    pub fn sample(self, phase: f32) -> f32 {
+        match self {
+            Curve::Constant => 1.0,
+            Curve::Sine => 0.5 - 0.5 * (phase * TAU).cos(),
+            Curve::Triangle => 1.0 - (2.0 * phase - 1.0).abs(),
+            Curve::Square => {
+                if (0.25..0.75).contains(&phase) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Periodic modulation of one environment quantity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    pub curve: Curve,
+    pub amplitude: f32,
+}
+
+impl Cycle {
+    pub fn new(curve: Curve, amplitude: f32) -> Self {
+        Self { curve, amplitude }
+    }
+
+    /// Multiplier in `1.0 - amplitude..=1.0`.
+    pub fn factor(&self, phase: f32) -> f32 {
+        1.0 - self.amplitude * (1.0 - self.curve.sample(phase))
+    }
+
+    /// Offset in `-amplitude..=amplitude`.
+    pub fn offset(&self, phase: f32) -> f32 {
+        self.amplitude * (2.0 * self.curve.sample(phase) - 1.0)
+    }
+}
+
+/// Cycles of light, temperature and nutrient input over one period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cycles {
+    pub light: Cycle,
+    /// Amplitude in degrees.
+    pub temperature: Cycle,
+    pub nutrients: Cycle,
+}
+
+/// Global environment clock with daily and seasonal cycles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clock {
+    /// Length of a day in ticks.
+    pub day_length: u64,
+    /// Length of a year in days.
+    pub year_length: u64,
+    pub daily: Cycles,
+    pub seasonal: Cycles,
+}
+
+impl Clock {
+    pub fn day_phase(&self, tick: u64) -> f32 {
+        let day_length = self.day_length.max(1);
+        (tick % day_length) as f32 / day_length as f32
+    }
+
+    pub fn season_phase(&self, tick: u64) -> f32 {
+        let year_length = self.day_length.max(1) * self.year_length.max(1);
+        (tick % year_length) as f32 / year_length as f32
+    }
+
+    pub fn day(&self, tick: u64) -> u64 {
+        tick / self.day_length.max(1)
+    }
+
+    /// How bright the day is, `1.0` at noon down to `1.0 - amplitude` of the daily light
+    /// cycle at midnight, so the view only dims as much as the light does.
+    pub fn daylight(&self, tick: u64) -> f32 {
+        self.daily
+            .light
+            .factor(self.day_phase(tick))
+            .clamp(0.0, 1.0)
+    }
+
+    pub fn light_factor(&self, tick: u64) -> f32 {
+        self.daily.light.factor(self.day_phase(tick))
+            * self.seasonal.light.factor(self.season_phase(tick))
+    }
+
+    pub fn temperature_offset(&self, tick: u64) -> f32 {
+        self.daily.temperature.offset(self.day_phase(tick))
+            + self.seasonal.temperature.offset(self.season_phase(tick))
+    }
+
+    pub fn nutrient_factor(&self, tick: u64) -> f32 {
+        self.daily.nutrients.factor(self.day_phase(tick))
+            * self.seasonal.nutrients.factor(self.season_phase(tick))
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            day_length: 1200,
+            year_length: 24,
+            daily: Cycles {
+                light: Cycle::new(Curve::Sine, 0.8),
+                temperature: Cycle::new(Curve::Sine, 5.0),
+                nutrients: Cycle::new(Curve::Constant, 0.0),
+            },
+            seasonal: Cycles {
+                light: Cycle::new(Curve::Sine, 0.3),
+                temperature: Cycle::new(Curve::Sine, 10.0),
+                nutrients: Cycle::new(Curve::Sine, 0.5),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_rise_from_the_start_to_the_middle_of_the_period() {
+        for (curve, quarter) in [
+            (Curve::Sine, 0.5),
+            (Curve::Triangle, 0.5),
+            (Curve::Square, 1.0),
+        ] {
+            assert_eq!(curve.sample(0.0), 0.0, "{curve:?}");
+            assert!((curve.sample(0.25) - quarter).abs() < 1e-6, "{curve:?}");
+            assert_eq!(curve.sample(0.5), 1.0, "{curve:?}");
+            assert!(
+                (curve.sample(0.75) - (1.0 - quarter)).abs() < 1e-6,
+                "{curve:?}"
+            );
+        }
+        for phase in [0.0, 0.25, 0.5, 0.75] {
+            assert_eq!(Curve::Constant.sample(phase), 1.0);
+        }
+    }
+
+    #[test]
+    fn phases_wrap_around_days_and_years() {
+        let clock = Clock {
+            day_length: 100,
+            year_length: 4,
+            ..Clock::default()
+        };
+        assert_eq!(clock.day_phase(150), 0.5);
+        assert_eq!(clock.day_phase(400), 0.0);
+        assert_eq!(clock.season_phase(200), 0.5);
+        assert_eq!(clock.season_phase(450), 0.125);
+        assert_eq!(clock.day(450), 4);
+    }
+
+    #[test]
+    fn daylight_dims_by_the_light_amplitude() {
+        let mut clock = Clock {
+            day_length: 100,
+            ..Clock::default()
+        };
+        let (midnight, noon) = (0, 50);
+        clock.daily.light = Cycle::new(Curve::Sine, 0.8);
+        assert!((clock.daylight(midnight) - 0.2).abs() < 1e-6);
+        assert_eq!(clock.daylight(noon), 1.0);
+
+        clock.daily.light = Cycle::new(Curve::Square, 0.0);
+        assert_eq!(clock.daylight(midnight), 1.0);
+        assert_eq!(clock.daylight(noon), 1.0);
+    }
+}
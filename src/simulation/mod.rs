@@ -0,0 +1,838 @@
+use std::collections::HashSet;
+
+use nalgebra::Vector2;
+
+use crate::{
+    automaton::{HashLife, LifeRule, RuleTable},
+    cell::Plasmid,
+    grid::{
+        bands::{in_band, map_bands},
+        chunks::ActiveChunks,
+        constants::SIZE_GRID,
+        Grid,
+    },
+};
+
+use actions::Intent;
+
+pub use clock::Clock;
+pub use rng::Rng;
+pub use settings::Settings;
+pub use stats::Statistics;
+
+mod actions;
+pub mod clock;
+pub mod rng;
+pub mod settings;
+pub mod stats;
+
+/// How the cells of the grid evolve.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Mode {
+    /// Living cells feeding, acting and evolving.
+    #[default]
+    Ecosystem,
+    /// A life-like cellular automaton over the occupied slots.
+    Life(LifeRule),
+    /// A multi-state automaton read from a Golly rule table.
+    Table(RuleTable),
+}
+
+/// Advances the world held by [`Grid`] one tick at a time.
+#[derive(Debug)]
+pub struct Simulation {
+    pub running: bool,
+    pub tick: u64,
+    pub mode: Mode,
+    pub settings: Settings,
+    pub clock: Clock,
+    pub statistics: Statistics,
+    /// Threads the tick is spread over; the outcome does not depend on it.
+    pub threads: usize,
+    seed: u64,
+    /// Slots cells were moved into during the current tick.
+    moved: HashSet<Vector2<usize>>,
+    /// Chunks the automaton modes update in the next generation.
+    chunks: ActiveChunks,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            running: false,
+            tick: 0,
+            mode: Mode::default(),
+            settings: Settings::default(),
+            clock: Clock::default(),
+            statistics: Statistics::default(),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            seed,
+            moved: HashSet::new(),
+            chunks: ActiveChunks::default(),
+        }
+    }
+
+    pub fn step(&mut self, grid: &mut Grid) {
+        let generation = match &mut self.mode {
+            Mode::Ecosystem => {
+                self.step_ecosystem(grid);
+                None
+            }
+            Mode::Life(rule) => Some(rule.step(grid, self.threads, &mut self.chunks)),
+            Mode::Table(table) => Some(table.step(grid, self.threads, &mut self.chunks)),
+        };
+        if let Some(generation) = generation {
+            self.statistics.births += generation.births;
+            self.statistics.deaths += generation.deaths;
+        }
+
+        self.tick += 1;
+    }
+
+    /// Chunks the automaton modes will update in the next generation.
+    pub fn active_chunks(&self) -> usize {
+        self.chunks.active_count()
+    }
+
+    /// Advances the life-like automaton by `2^log_generations` generations at once
+    /// with [`HashLife`], ending where as many calls to [`Simulation::step`] would.
+    /// Survivors keep their cells and newborns are default cells. Does nothing
+    /// outside of [`Mode::Life`].
+    pub fn leap(&mut self, grid: &mut Grid, log_generations: u8) {
+        let Mode::Life(rule) = &self.mode else {
+            return;
+        };
+        let mut hashlife = HashLife::from_grid(grid, *rule);
+        hashlife.memo_limit = self.settings.hashlife_memo_limit;
+        hashlife.advance(log_generations);
+
+        let mut next = hashlife.to_layout();
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                match (grid.cells.contains(pos), next[pos].take()) {
+                    (true, None) => {
+                        grid.remove_cell(pos);
+                        self.statistics.deaths += 1;
+                    }
+                    (false, Some(cell)) => {
+                        grid.spawn_cell(pos, cell);
+                        self.statistics.births += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.tick += hashlife.generation;
+    }
+
+    fn step_ecosystem(&mut self, grid: &mut Grid) {
+        self.update_remains(grid);
+        self.update_resources(grid);
+        self.update_signals(grid);
+        grid.light.update(&grid.layout_zones, &grid.cells);
+
+        // Every phase reading the grid runs on bands in parallel against the state
+        // left by the previous phase; the results are then written in order.
+        let positions = grid.cell_positions();
+        let metabolisms = map_bands(self.threads, |band| {
+            in_band(&positions, band)
+                .iter()
+                .filter_map(|&pos| self.metabolism(grid, pos))
+                .collect()
+        });
+        let mut alive = vec![];
+        for metabolism in metabolisms {
+            let pos = metabolism.pos;
+            if self.metabolize(grid, metabolism) {
+                alive.push(pos);
+            }
+        }
+
+        self.share_energy(grid);
+
+        let decisions = map_bands(self.threads, |band| {
+            in_band(&alive, band)
+                .iter()
+                .filter_map(|&pos| {
+                    let cell = grid.cells.get(pos)?;
+                    let mut rng = Rng::for_slot(self.seed, self.tick, pos);
+                    let infections = cell.infections(pos, grid, &mut rng);
+                    let action = cell.decide(pos, grid, &self.settings, &mut rng);
+                    Some((Intent::new(pos, action, cell.mass, rng), infections))
+                })
+                .collect()
+        });
+        let (mut intents, infections): (Vec<_>, Vec<_>) = decisions.into_iter().unzip();
+        let infections = infections.into_iter().flatten().collect();
+
+        self.spread_plasmids(grid, infections);
+
+        actions::resolve_conflicts(&mut intents);
+        self.moved.clear();
+        for mut intent in intents {
+            self.perform(grid, intent.pos, intent.action, &mut intent.rng);
+        }
+
+        self.update_prevalence(grid);
+    }
+
+    fn spread_plasmids(&mut self, grid: &mut Grid, infections: Vec<(Vector2<usize>, Plasmid)>) {
+        for (target, plasmid) in infections {
+            if let Some(mut host) = grid.cells.get_mut(target) {
+                if host.infect(plasmid) {
+                    self.statistics.infections += 1;
+                }
+            }
+        }
+    }
+
+    fn update_prevalence(&mut self, grid: &Grid) {
+        let positions = grid.cell_positions();
+        let infected = positions
+            .iter()
+            .filter(|&&pos| {
+                grid.cells
+                    .get(pos)
+                    .is_some_and(|cell| !cell.plasmids.is_empty())
+            })
+            .count();
+        self.statistics.prevalence = if positions.is_empty() {
+            0.0
+        } else {
+            infected as f32 / positions.len() as f32
+        };
+    }
+
+    /// Evens out the energy of bonded cells.
+    fn share_energy(&self, grid: &mut Grid) {
+        for pos in grid.cell_positions() {
+            for other in grid.bonds.bonded(pos) {
+                // Every bond is visited from both ends; handle it once.
+                if (other.x, other.y) < (pos.x, pos.y) {
+                    continue;
+                }
+
+                let (Some(a), Some(b)) = (grid.cells.get(pos), grid.cells.get(other)) else {
+                    continue;
+                };
+                let flow = (a.energy - b.energy) * self.settings.bond_sharing / 2.0;
+
+                if let Some(a) = grid.cells.get_mut(pos) {
+                    *a.energy -= flow;
+                }
+                if let Some(b) = grid.cells.get_mut(other) {
+                    *b.energy += flow;
+                }
+            }
+        }
+    }
+
+    /// Lets dead matter decay into nutrients.
+    fn update_remains(&self, grid: &mut Grid) {
+        let s = &self.settings;
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                let decayed = grid.remains[pos] * s.remains_decay;
+                if decayed > 0.0 {
+                    grid.remains[pos] -= decayed;
+                    grid.nutrients[pos] += decayed * s.remains_nutrient_yield;
+                }
+            }
+        }
+    }
+
+    /// Replenishes the organic and mineral fields from the zones and lets them diffuse.
+    fn update_resources(&self, grid: &mut Grid) {
+        let s = &self.settings;
+        let factor = self.clock.nutrient_factor(self.tick);
+        let Grid {
+            layout_zones: zones,
+            nutrients,
+            minerals,
+            ..
+        } = grid;
+        let is_open = |pos: Vector2<usize>| !zones[pos].is_some_and(|zone| zone.blocks_cells());
+
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                if !is_open(pos) {
+                    continue;
+                }
+
+                let env = zones[pos].map(|zone| zone.environment).unwrap_or_default();
+                replenish(
+                    &mut nutrients[pos],
+                    env.nutrient_supply * factor,
+                    s.nutrient_capacity,
+                );
+                replenish(&mut minerals[pos], env.mineral_supply, s.mineral_capacity);
+            }
+        }
+
+        nutrients.diffuse(s.nutrient_diffusion, is_open);
+        minerals.diffuse(s.mineral_diffusion, is_open);
+    }
+
+    /// Lets the signal chemicals decay and spread.
+    fn update_signals(&self, grid: &mut Grid) {
+        let zones = &grid.layout_zones;
+        grid.signals.update(&self.settings.signals, |pos| {
+            !zones[pos].is_some_and(|zone| zone.blocks_cells())
+        });
+    }
+
+    /// Effect of the environment of the slot on its cell, if there is one.
+    fn metabolism(&self, grid: &Grid, pos: Vector2<usize>) -> Option<Metabolism> {
+        let env = grid.environment(pos);
+        let light = grid.light(pos) * self.clock.light_factor(self.tick);
+        let temperature = env.temperature + self.clock.temperature_offset(self.tick);
+        let cell = grid.cells.get(pos)?;
+
+        let s = &self.settings;
+        let physiology = cell.cell_type.physiology();
+        let temperature_factor =
+            1.0 + (temperature - s.optimal_temperature).abs() * s.temperature_sensitivity;
+
+        let age = cell.age + 1;
+        let age_factor = if s.ageing {
+            1.0 + s.senescence * age as f32 / s.max_age.max(1) as f32
+        } else {
+            1.0
+        };
+
+        // A cell takes up only as much food and minerals as its pathways digest.
+        let enzymes = cell.genome.enzymes;
+        let organic = grid.nutrients[pos]
+            .min(s.nutrient_uptake * physiology.nutrient_intake * enzymes.heterotrophy);
+        let minerals = grid.minerals[pos]
+            .min(s.mineral_uptake * physiology.mineral_intake * enzymes.chemosynthesis);
+
+        let mut energy = cell.energy;
+        energy += light * s.light_gain * physiology.light_intake * enzymes.photosynthesis
+            + organic * s.nutrient_gain
+            + minerals * s.mineral_gain;
+        energy -= cell.mass * s.basal_cost * physiology.upkeep * temperature_factor * age_factor
+            + env.toxicity
+            + enzymes.total() * s.enzyme_cost
+            + cell.plasmid_upkeep();
+        for &amount in cell.genome.emission.iter() {
+            if amount > 0.0 {
+                energy -= amount * s.signal_cost;
+            }
+        }
+
+        Some(Metabolism {
+            pos,
+            organic,
+            minerals,
+            energy,
+            dies: energy <= 0.0 || (s.ageing && age >= s.max_age),
+        })
+    }
+
+    /// Writes the metabolism of a cell into the grid. Returns `false` if the cell died.
+    fn metabolize(&mut self, grid: &mut Grid, metabolism: Metabolism) -> bool {
+        let pos = metabolism.pos;
+        let Some(cell) = grid.cells.get_mut(pos) else {
+            return false;
+        };
+
+        grid.nutrients[pos] -= metabolism.organic;
+        grid.minerals[pos] -= metabolism.minerals;
+        *cell.age += 1;
+        *cell.energy = metabolism.energy;
+        for (channel, &amount) in cell.genome.emission.iter().enumerate() {
+            if amount > 0.0 {
+                grid.signals.emit(pos, channel, amount);
+            }
+        }
+
+        if metabolism.dies {
+            self.kill(grid, pos);
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Change a tick of metabolism makes to a cell and its slot.
+struct Metabolism {
+    pos: Vector2<usize>,
+    /// Organic food taken up from the slot.
+    organic: f32,
+    /// Minerals taken up from the slot.
+    minerals: f32,
+    /// Energy of the cell afterwards.
+    energy: f32,
+    dies: bool,
+}
+
+/// Adds `supply` to a resource value without letting it grow past `capacity`.
+fn replenish(value: &mut f32, supply: f32, capacity: f32) {
+    if supply > 0.0 && *value < capacity {
+        *value = (*value + supply).min(capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::{
+        automaton::{hashlife::MEMO_LIMIT, table::PRESETS},
+        cell::{Action, Brain, Cell, CellId, CellType, Enzymes},
+        grid::chunks::CHUNK_SIZE,
+        simulation::stats::PredationEvent,
+        zone::{Zone, ZoneKind},
+    };
+
+    fn seeded() -> Grid {
+        let mut grid = Grid::new();
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                let pos = Vector2::new(x, y);
+                if (x + y) % 7 == 0 && x % 4 == 0 {
+                    grid.set_zone(pos, Some(Zone::new(ZoneKind::LightSource)));
+                } else if (x * 3 + y) % 11 == 0 {
+                    grid.set_zone(pos, Some(Zone::new(ZoneKind::Soil)));
+                }
+
+                if x % 3 == 0 && y % 4 == 0 {
+                    let mut cell = Cell {
+                        energy: 500.0,
+                        color: [x as f32 / 100.0, y as f32 / 100.0, 0.5],
+                        plasmids: (x % 5 == 0).then(Plasmid::default).into_iter().collect(),
+                        brain: (y % 8 == 0).then(Brain::default),
+                        ..Cell::default()
+                    };
+                    cell.genome.aggression = (x % 9) as f32 / 10.0;
+                    cell.genome.motility = (y % 8) as f32 / 10.0;
+                    cell.genome.scavenging = 0.2;
+                    cell.genome.adhesion = (x % 2) as f32;
+                    cell.genome.mating = (y % 3) as f32 / 2.0;
+                    grid.spawn_cell(pos, cell);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Dense random cells in the corner of the grid, cut by a wall.
+    fn walled_soup() -> Grid {
+        let mut grid = Grid::new();
+        for y in 60..96 {
+            grid.set_zone(Vector2::new(15, y), Some(Zone::new(ZoneKind::Wall)));
+        }
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for x in 0..30 {
+            for y in 70..SIZE_GRID[1] {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let pos = Vector2::new(x, y);
+                if state % 3 == 1 && !grid.is_blocked(pos) {
+                    grid.spawn_cell(pos, Cell::default());
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Dense random cells in the middle of the grid.
+    fn soup() -> Grid {
+        let mut grid = Grid::new();
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        for x in 30..70 {
+            for y in 30..70 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if state % 3 == 1 {
+                    grid.spawn_cell(Vector2::new(x, y), Cell::default());
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Everything the tick writes, with floats printed exactly.
+    fn snapshot(grid: &Grid, simulation: &Simulation) -> String {
+        let cells: Vec<_> = grid
+            .cell_positions()
+            .into_iter()
+            .map(|pos| (pos, grid.cells.get(pos).map(|cell| cell.to_cell())))
+            .collect();
+        format!(
+            "{cells:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+            grid.bonds,
+            grid.nutrients,
+            grid.minerals,
+            grid.remains,
+            grid.signals,
+            simulation.statistics
+        )
+    }
+
+    fn run(mut grid: Grid, mode: Mode, threads: usize, ticks: usize) -> String {
+        let mut simulation = Simulation::new(42);
+        simulation.mode = mode;
+        simulation.threads = threads;
+        for _ in 0..ticks {
+            simulation.step(&mut grid);
+        }
+
+        snapshot(&grid, &simulation)
+    }
+
+    #[test]
+    fn ecosystem_does_not_depend_on_threads() {
+        let serial = run(seeded(), Mode::Ecosystem, 1, 20);
+        for threads in [3, 8] {
+            assert!(
+                run(seeded(), Mode::Ecosystem, threads, 20) == serial,
+                "{threads} threads"
+            );
+        }
+    }
+
+    #[test]
+    fn uptake_follows_the_pathways() {
+        let mut grid = Grid::new();
+        let pos = Vector2::new(5, 5);
+        let mut cell = Cell::default();
+        cell.genome.enzymes = Enzymes {
+            photosynthesis: 1.0,
+            heterotrophy: 0.0,
+            chemosynthesis: 0.5,
+        };
+        grid.spawn_cell(pos, cell);
+        grid.nutrients[pos] = 100.0;
+        grid.minerals[pos] = 100.0;
+
+        let simulation = Simulation::new(42);
+        let metabolism = simulation.metabolism(&grid, pos).unwrap();
+        let s = &simulation.settings;
+        let physiology = grid.cells.get(pos).unwrap().cell_type.physiology();
+        assert_eq!(metabolism.organic, 0.0);
+        assert_eq!(
+            metabolism.minerals,
+            s.mineral_uptake * physiology.mineral_intake * 0.5
+        );
+    }
+
+    #[test]
+    fn attacks_win_by_mass_and_feed_the_attacker() {
+        let (pos, target) = (Vector2::new(5, 5), Vector2::new(6, 5));
+        let mut grid = Grid::new();
+        let mut simulation = Simulation::new(42);
+        let mut rng = Rng::new(7);
+        // Sets up a fresh fight on the same grid and tells whether the attacker won.
+        let mut attack = |grid: &mut Grid, simulation: &mut Simulation, cell_type| {
+            grid.remove_cell(pos);
+            grid.remove_cell(target);
+            grid.remains[target] = 0.0;
+            simulation.statistics = Statistics::default();
+
+            let attacker = Cell {
+                mass: 24.0,
+                ..Cell::default()
+            };
+            let victim = Cell {
+                mass: 8.0,
+                energy: 100.0,
+                cell_type,
+                ..Cell::default()
+            };
+            grid.spawn_cell(pos, attacker);
+            grid.spawn_cell(target, victim);
+            simulation.perform(grid, pos, Action::Attack(target), &mut rng);
+
+            simulation.statistics.predations == 1
+        };
+
+        // Defence weights the mass of the victim: 24 / (24 + 8) and 24 / (24 + 3 * 8).
+        for (cell_type, odds) in [(CellType::Stem, 0.75), (CellType::Defender, 0.5)] {
+            let trials = 2000;
+            let wins = (0..trials)
+                .filter(|_| attack(&mut grid, &mut simulation, cell_type))
+                .count();
+            let rate = wins as f32 / trials as f32;
+            assert!((rate - odds).abs() < 0.04, "{cell_type:?} won {rate}");
+        }
+
+        // The winner eats the share of the victim given by the efficiency and leaves the
+        // rest as remains.
+        while !attack(&mut grid, &mut simulation, CellType::Stem) {}
+        let s = simulation.settings.clone();
+        let attacker = grid.cells.get(pos).unwrap();
+        assert!(!grid.cells.contains(target));
+        assert_eq!(
+            attacker.energy,
+            256.0 + 100.0 * s.predation_efficiency - s.attack_cost
+        );
+        assert_eq!(attacker.mass, 24.0 + 8.0 * s.predation_efficiency);
+        assert_eq!(grid.remains[target], 8.0 * (1.0 - s.predation_efficiency));
+        assert_eq!(simulation.statistics.deaths, 1);
+        assert_eq!(
+            simulation.statistics.recent_predations.back(),
+            Some(&PredationEvent {
+                tick: 0,
+                attacker: pos,
+                victim: target,
+                success: true,
+                energy: 100.0 * s.predation_efficiency,
+                mass: 8.0 * s.predation_efficiency,
+            })
+        );
+
+        // The loser only pays for the attack.
+        while attack(&mut grid, &mut simulation, CellType::Stem) {}
+        let attacker = grid.cells.get(pos).unwrap();
+        assert!(grid.cells.contains(target));
+        assert_eq!(attacker.energy, 256.0 - s.attack_cost);
+        assert_eq!(attacker.mass, 24.0);
+        assert_eq!(grid.remains[target], 0.0);
+        assert_eq!(simulation.statistics.failed_attacks, 1);
+        assert_eq!(simulation.statistics.deaths, 0);
+        assert!(!simulation.statistics.recent_predations[0].success);
+    }
+
+    #[test]
+    fn remains_decay_into_nutrients_and_feed_scavengers() {
+        let mut grid = Grid::new();
+        let mut simulation = Simulation::new(42);
+        simulation.settings.remains_nutrient_yield = 0.5;
+        for x in 0..SIZE_GRID[0] {
+            for y in 0..SIZE_GRID[1] {
+                grid.remains[Vector2::new(x, y)] = ((x * 31 + y * 17) % 23) as f32;
+            }
+        }
+
+        // Decay moves dead matter into the organic field without losing any of it.
+        let s = simulation.settings.clone();
+        let mass = |grid: &Grid| {
+            grid.remains.sum() + grid.nutrients.sum() / s.remains_nutrient_yield as f64
+        };
+        let (remains, total) = (grid.remains.sum(), mass(&grid));
+        for _ in 0..100 {
+            simulation.update_remains(&mut grid);
+        }
+        assert!(grid.remains.sum() < remains);
+        assert!((mass(&grid) - total).abs() / total < 1e-5);
+
+        // A dead cell leaves its mass behind for scavengers to eat a bite at a time.
+        let (pos, target) = (Vector2::new(5, 5), Vector2::new(6, 5));
+        grid.remains[target] = 0.0;
+        grid.spawn_cell(pos, Cell::default());
+        grid.spawn_cell(target, Cell::default());
+        simulation.kill(&mut grid, target);
+        assert_eq!(grid.remains[target], Cell::default().mass);
+
+        let bites = (Cell::default().mass / s.scavenge_amount).ceil() as usize;
+        for _ in 0..bites + 1 {
+            let mut rng = Rng::new(7);
+            simulation.perform(&mut grid, pos, Action::Scavenge(target), &mut rng);
+        }
+        let scavenger = grid.cells.get(pos).unwrap();
+        assert_eq!(grid.remains[target], 0.0);
+        assert_eq!(
+            scavenger.energy,
+            Cell::default().energy + Cell::default().mass * s.scavenge_gain
+        );
+    }
+
+    #[test]
+    fn automata_do_not_depend_on_threads() {
+        let tables = PRESETS.map(|(_, source)| Mode::Table(source.parse().unwrap()));
+        for mode in [Mode::Life(LifeRule::CONWAY)].into_iter().chain(tables) {
+            let serial = run(soup(), mode.clone(), 1, 50);
+            for threads in [2, 3, 8] {
+                assert!(
+                    run(soup(), mode.clone(), threads, 50) == serial,
+                    "{mode:?} on {threads} threads"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn active_chunks_match_a_full_sweep() {
+        let table = PRESETS[1].1.parse().unwrap();
+        for mode in [Mode::Life(LifeRule::CONWAY), Mode::Table(table)] {
+            let run = |full_sweep: bool| {
+                let mut grid = soup();
+                let mut simulation = Simulation::new(42);
+                simulation.mode = mode.clone();
+                for tick in 0..80 {
+                    if full_sweep {
+                        simulation.chunks = ActiveChunks::default();
+                    }
+                    // Edits between generations must wake their chunks. The wall
+                    // holds back the birth that would close the corner into a block
+                    // until it is taken down, and nothing else wakes its chunk.
+                    let wall = Vector2::new(11, 11);
+                    match tick {
+                        20 => {
+                            grid.set_zone(wall, Some(Zone::new(ZoneKind::Wall)));
+                            for (x, y) in [(10, 10), (10, 11), (11, 10)] {
+                                grid.spawn_cell(Vector2::new(x, y), Cell::default());
+                            }
+                        }
+                        40 => grid.set_zone(wall, None),
+                        60 => {
+                            for y in 10..13 {
+                                grid.spawn_cell(Vector2::new(20, y), Cell::default());
+                            }
+                        }
+                        _ => {}
+                    }
+                    simulation.step(&mut grid);
+                }
+
+                snapshot(&grid, &simulation)
+            };
+
+            assert!(run(false) == run(true), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn ecosystem_edits_wake_chunks() {
+        let table: RuleTable = PRESETS[1].1.parse().unwrap();
+        for automaton in [Mode::Life(LifeRule::CONWAY), Mode::Table(table)] {
+            let run = |full_sweep: bool| {
+                // A still block in every chunk, of cells that move, and starving
+                // cells that die.
+                let mut grid = Grid::new();
+                for x in (2..SIZE_GRID[0]).step_by(CHUNK_SIZE) {
+                    for y in (2..SIZE_GRID[1]).step_by(CHUNK_SIZE) {
+                        for (dx, dy) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                            let mut cell = Cell {
+                                energy: if (x + y) % 3 == 1 { 1.0 } else { 500.0 },
+                                ..Cell::default()
+                            };
+                            cell.genome.motility = 0.5;
+                            grid.spawn_cell(Vector2::new(x + dx, y + dy), cell);
+                        }
+                    }
+                }
+                let mut simulation = Simulation::new(42);
+                for tick in 0..50 {
+                    // The ecosystem moves, feeds and kills cells between the runs
+                    // of the automaton, which must then pick up where it did.
+                    simulation.mode = match tick {
+                        10..20 => Mode::Ecosystem,
+                        _ => automaton.clone(),
+                    };
+                    if full_sweep {
+                        simulation.chunks = ActiveChunks::default();
+                    }
+                    simulation.step(&mut grid);
+                }
+
+                snapshot(&grid, &simulation)
+            };
+
+            assert!(run(false) == run(true), "{automaton:?}");
+        }
+    }
+
+    #[test]
+    fn light_follows_edits_like_a_full_sweep() {
+        let run = |full_sweep: bool| {
+            let mut grid = seeded();
+            let mut simulation = Simulation::new(42);
+            let mut light = vec![];
+            for _ in 0..30 {
+                if full_sweep {
+                    for x in 0..SIZE_GRID[0] {
+                        for y in 0..SIZE_GRID[1] {
+                            grid.light.mark(Vector2::new(x, y));
+                        }
+                    }
+                }
+                simulation.step(&mut grid);
+                light.extend(grid.cell_positions().into_iter().map(|pos| grid.light(pos)));
+            }
+
+            (snapshot(&grid, &simulation), light)
+        };
+
+        assert!(run(false) == run(true));
+    }
+
+    #[test]
+    fn cell_ids_follow_cells() {
+        let mut grid = seeded();
+        let mut simulation = Simulation::new(42);
+        let ids = |grid: &Grid| -> HashMap<CellId, Vector2<usize>> {
+            grid.cell_positions()
+                .into_iter()
+                .filter_map(|pos| grid.cells.get(pos).map(|cell| (cell.id, pos)))
+                .collect()
+        };
+
+        let mut alive = ids(&grid);
+        let mut dead: HashSet<CellId> = HashSet::new();
+        let mut moved = false;
+        for _ in 0..30 {
+            simulation.step(&mut grid);
+            let now = ids(&grid);
+            assert_eq!(
+                now.len(),
+                grid.cell_positions().len(),
+                "identifiers are shared"
+            );
+            assert_eq!(now.len(), grid.cell_ids.len());
+            for (&id, &pos) in &now {
+                assert!(id.is_assigned() && id < grid.cell_ids.next());
+                assert_eq!(grid.cell_ids.position(id), Some(pos));
+                assert!(!dead.contains(&id), "{id} was issued again");
+                moved |= alive.get(&id).is_some_and(|&old| old != pos);
+            }
+            dead.extend(alive.keys().filter(|id| !now.contains_key(id)));
+            alive = now;
+        }
+        assert!(moved && !dead.is_empty());
+    }
+
+    #[test]
+    fn leaps_match_steps() {
+        for rule in ["B3/S23", "B36/S23", "B0123/S01234"] {
+            let rule: LifeRule = rule.parse().unwrap();
+            for log_generations in 0..=5 {
+                let mut stepped = walled_soup();
+                let mut simulation = Simulation::new(42);
+                simulation.mode = Mode::Life(rule);
+                for _ in 0..1 << log_generations {
+                    simulation.step(&mut stepped);
+                }
+
+                // A small memo limit splits the leap into shorter ones.
+                for memo_limit in [MEMO_LIMIT, 1 << 10] {
+                    let mut leapt = walled_soup();
+                    let mut leaping = Simulation::new(42);
+                    leaping.mode = Mode::Life(rule);
+                    leaping.settings.hashlife_memo_limit = memo_limit;
+                    leaping.leap(&mut leapt, log_generations);
+
+                    assert_eq!(leaping.tick, simulation.tick);
+                    assert!(
+                        stepped.cell_positions() == leapt.cell_positions(),
+                        "{rule} differs after 2^{log_generations} generations"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use nalgebra::Vector2;
+
+/// Small SplitMix64 generator.
+///
+/// Every cell gets its own generator seeded from the tick and its position,
+/// so the outcome of a tick does not depend on the order cells are visited in.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn for_slot(seed: u64, tick: u64, pos: Vector2<usize>) -> Self {
+        let mut rng = Self::new(seed ^ tick.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        rng.state ^= rng.next_u64() ^ ((pos.x as u64) << 32) ^ pos.y as u64;
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform value in `-1.0..1.0`.
+    pub fn next_signed(&mut self) -> f32 {
+        self.next_f32() * 2.0 - 1.0
+    }
+
+    /// Uniform index in `0..len`.
+    pub fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    automaton::hashlife::MEMO_LIMIT,
+    grid::signals::{SignalChannel, SIGNAL_CHANNELS},
+};
+
+/// Tunable constants of the simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Energy gained per tick per unit of light reaching the slot.
+    pub light_gain: f32,
+    /// Energy gained per unit of organic food taken up.
+    pub nutrient_gain: f32,
+    /// Nutrients a cell with full heterotrophy can take up from its slot per tick.
+    pub nutrient_uptake: f32,
+    /// Rate at which nutrients spread between neighbouring slots per tick.
+    pub nutrient_diffusion: f32,
+    /// Amount of nutrients above which zones stop replenishing a slot.
+    pub nutrient_capacity: f32,
+    /// Energy gained per unit of minerals taken up.
+    pub mineral_gain: f32,
+    /// Minerals a cell with full chemosynthesis can take up from its slot per tick.
+    pub mineral_uptake: f32,
+    /// Rate at which minerals spread between neighbouring slots per tick.
+    pub mineral_diffusion: f32,
+    /// Amount of minerals above which zones stop replenishing a slot.
+    pub mineral_capacity: f32,
+    /// Energy spent per tick per unit of enzyme level.
+    pub enzyme_cost: f32,
+    /// Energy spent per tick per unit of mass.
+    pub basal_cost: f32,
+    /// Temperature at which the metabolic cost is the lowest.
+    pub optimal_temperature: f32,
+    /// Relative increase of the metabolic cost per degree away from the optimum.
+    pub temperature_sensitivity: f32,
+    /// Whether cells grow costlier with age and die of old age.
+    pub ageing: bool,
+    /// Age in ticks at which a cell dies.
+    pub max_age: u64,
+    /// Relative increase of the basal cost at the maximum age.
+    pub senescence: f32,
+    /// Energy at which a cell divides into two.
+    pub division_energy: f32,
+    /// Energy a cell needs to mate.
+    pub mating_energy: f32,
+    /// Fraction of the energy of each parent given to their offspring.
+    pub mating_share: f32,
+    /// Least kinship two cells need to mate.
+    pub mating_kinship: f32,
+    /// Largest genome distance two cells can have and still mate.
+    pub mating_distance: f32,
+    /// Fraction of the energy and mass of an eaten cell gained by its predator.
+    pub predation_efficiency: f32,
+    /// Energy spent on an attack, whether it succeeds or not.
+    pub attack_cost: f32,
+    /// Mass a cell cannot grow beyond by eating others.
+    pub max_mass: f32,
+    /// Energy spent per unit of mass on moving into a slot with movement cost `1.0`.
+    pub move_cost: f32,
+    /// Fraction of the energy difference evened out along a bond per tick.
+    pub bond_sharing: f32,
+    /// Chance per tick of a cell without any adhesion to break one of its bonds.
+    pub bond_break_chance: f32,
+    /// Least kinship two cells need to bond.
+    pub bond_kinship: f32,
+    /// Spreading and decay of every signal channel.
+    pub signals: [SignalChannel; SIGNAL_CHANNELS],
+    /// Energy spent per unit of signal chemical emitted.
+    pub signal_cost: f32,
+    /// Fraction of dead matter decaying into nutrients per tick.
+    pub remains_decay: f32,
+    /// Nutrients produced per unit of decayed dead matter.
+    pub remains_nutrient_yield: f32,
+    /// Dead matter a scavenger can eat per tick.
+    pub scavenge_amount: f32,
+    /// Energy gained per unit of dead matter eaten.
+    pub scavenge_gain: f32,
+    /// Maximum drift of the heritable traits of a daughter cell before the zone multiplier.
+    pub mutation_strength: f32,
+    /// Most nodes and results HashLife keeps during a leap; leaps needing more are split.
+    pub hashlife_memo_limit: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            light_gain: 2.0,
+            nutrient_gain: 1.0,
+            nutrient_uptake: 1.0,
+            nutrient_diffusion: 0.1,
+            nutrient_capacity: 100.0,
+            mineral_gain: 1.5,
+            mineral_uptake: 1.0,
+            mineral_diffusion: 0.02,
+            mineral_capacity: 50.0,
+            enzyme_cost: 0.05,
+            basal_cost: 0.05,
+            optimal_temperature: 20.0,
+            temperature_sensitivity: 0.05,
+            ageing: true,
+            max_age: 20000,
+            senescence: 2.0,
+            division_energy: 512.0,
+            mating_energy: 256.0,
+            mating_share: 0.25,
+            mating_kinship: 0.8,
+            mating_distance: 0.2,
+            predation_efficiency: 0.5,
+            attack_cost: 5.0,
+            max_mass: 64.0,
+            move_cost: 0.05,
+            bond_sharing: 0.1,
+            bond_break_chance: 0.01,
+            bond_kinship: 0.9,
+            signals: [SignalChannel::default(); SIGNAL_CHANNELS],
+            signal_cost: 1.0,
+            remains_decay: 0.005,
+            remains_nutrient_yield: 1.0,
+            scavenge_amount: 2.0,
+            scavenge_gain: 8.0,
+            mutation_strength: 0.05,
+            hashlife_memo_limit: MEMO_LIMIT,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+use nalgebra::Vector2;
+
+/// How many of the latest predation events are kept.
+pub const RECENT_EVENTS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PredationEvent {
+    pub tick: u64,
+    pub attacker: Vector2<usize>,
+    pub victim: Vector2<usize>,
+    pub success: bool,
+    /// Energy gained by the attacker.
+    pub energy: f32,
+    /// Mass gained by the attacker.
+    pub mass: f32,
+}
+
+/// Counters of what happened since the simulation started.
+#[derive(Debug, Clone, Default)]
+pub struct Statistics {
+    pub births: u64,
+    /// Births from mating, also counted in `births`.
+    pub sexual_births: u64,
+    pub deaths: u64,
+    pub predations: u64,
+    pub failed_attacks: u64,
+    pub moves: u64,
+    /// Plasmids injected into neighbouring cells.
+    pub infections: u64,
+    /// Share of living cells carrying at least one plasmid, as of the last tick.
+    pub prevalence: f32,
+    pub recent_predations: VecDeque<PredationEvent>,
+}
+
+impl Statistics {
+    pub fn record_predation(&mut self, event: PredationEvent) {
+        if event.success {
+            self.predations += 1;
+        } else {
+            self.failed_attacks += 1;
+        }
+
+        if self.recent_predations.len() == RECENT_EVENTS {
+            self.recent_predations.pop_front();
+        }
+        self.recent_predations.push_back(event);
+    }
+}
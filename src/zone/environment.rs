@@ -0,0 +1,35 @@
+/// Environmental properties of a zone.
+///
+/// The simulation reads them for every cell standing on the zone; slots
+/// without a zone use [`Environment::default`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Environment {
+    /// Ambient light intensity, `0.0..=1.0`; light sources emit it instead.
+    pub light: f32,
+    /// Temperature in degrees; the further from the optimum, the higher the metabolic cost.
+    pub temperature: f32,
+    /// Organic food added to the nutrient field of the slot per tick.
+    pub nutrient_supply: f32,
+    /// Minerals added to the mineral field of the slot per tick.
+    pub mineral_supply: f32,
+    /// Energy drained per tick from every cell.
+    pub toxicity: f32,
+    /// Multiplier applied to the mutation strength of cells born here.
+    pub mutation_rate: f32,
+    /// Multiplier applied to the energy spent on moving into this slot.
+    pub movement_cost: f32,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            light: 0.5,
+            temperature: 20.0,
+            nutrient_supply: 0.0,
+            mineral_supply: 0.0,
+            toxicity: 0.0,
+            mutation_rate: 1.0,
+            movement_cost: 1.0,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::Environment;
+
+/// Preset semantics of a zone.
+///
+/// The discriminant is passed to `zone.frag` to pick the look of the zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoneKind {
+    /// Holds organic food and a few minerals.
+    #[default]
+    Soil = 0,
+    /// Cheap to move through; carries dissolved minerals.
+    Water = 1,
+    /// Blocks cells.
+    Wall = 2,
+    /// Emits light.
+    LightSource = 3,
+    /// Drains energy, but is rich in minerals.
+    Toxic = 4,
+}
+
+impl ZoneKind {
+    pub const ALL: [ZoneKind; 5] = [
+        ZoneKind::Soil,
+        ZoneKind::Water,
+        ZoneKind::Wall,
+        ZoneKind::LightSource,
+        ZoneKind::Toxic,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ZoneKind::Soil => "Soil",
+            ZoneKind::Water => "Water",
+            ZoneKind::Wall => "Wall",
+            ZoneKind::LightSource => "Light source",
+            ZoneKind::Toxic => "Toxic",
+        }
+    }
+
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            ZoneKind::Soil => [0.45, 0.3, 0.15],
+            ZoneKind::Water => [0.15, 0.35, 0.7],
+            ZoneKind::Wall => [0.4, 0.4, 0.4],
+            ZoneKind::LightSource => [0.95, 0.85, 0.35],
+            ZoneKind::Toxic => [0.5, 0.8, 0.1],
+        }
+    }
+
+    pub fn environment(self) -> Environment {
+        let default = Environment::default();
+        match self {
+            ZoneKind::Soil => Environment {
+                nutrient_supply: 0.5,
+                mineral_supply: 0.1,
+                movement_cost: 2.0,
+                ..default
+            },
+            ZoneKind::Water => Environment {
+                temperature: 15.0,
+                mineral_supply: 0.3,
+                movement_cost: 0.5,
+                ..default
+            },
+            ZoneKind::Wall => Environment {
+                light: 0.0,
+                ..default
+            },
+            ZoneKind::LightSource => Environment {
+                light: 1.0,
+                temperature: 25.0,
+                ..default
+            },
+            ZoneKind::Toxic => Environment {
+                toxicity: 1.0,
+                mineral_supply: 0.8,
+                mutation_rate: 3.0,
+                ..default
+            },
+        }
+    }
+
+    /// Whether cells are kept out of a zone of this kind: never placed on, born into
+    /// or moved into it.
+    pub fn blocks_cells(self) -> bool {
+        self == ZoneKind::Wall
+    }
+}
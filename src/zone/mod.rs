@@ -7,13 +7,30 @@ use crate::{
     opengl::prelude::{get_location, GetId, Program, Shader, Vao, Vbo},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub use environment::Environment;
+pub use kind::ZoneKind;
+
+pub mod environment;
+pub mod kind;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Zone {
+    pub(crate) kind: ZoneKind,
     pub(crate) color: [f32; 3],
+    pub(crate) environment: Environment,
 }
 
 impl Zone {
-    pub fn create_render_data(&self, pos: (f32, f32), borders: i32) -> [f32; 48] {
+    /// Zone with the colour and environment preset of `kind`.
+    pub fn new(kind: ZoneKind) -> Self {
+        Self {
+            kind,
+            color: kind.color(),
+            environment: kind.environment(),
+        }
+    }
+
+    pub fn create_render_data(&self, pos: (f32, f32), borders: i32) -> [f32; 54] {
         let (x, y) = (pos.0 * SIZE_RENDER_CELL_GRID, pos.1 * SIZE_RENDER_CELL_GRID);
         let vertices = [
             x,
@@ -24,6 +41,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
             x + SIZE_RENDER_CELL_GRID,
             y,
             1.0,
@@ -32,6 +50,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
             x,
             y + SIZE_RENDER_CELL_GRID,
             0.0,
@@ -40,6 +59,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
             x,
             y + SIZE_RENDER_CELL_GRID,
             0.0,
@@ -48,6 +68,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
             x + SIZE_RENDER_CELL_GRID,
             y + SIZE_RENDER_CELL_GRID,
             1.0,
@@ -56,6 +77,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
             x + SIZE_RENDER_CELL_GRID,
             y,
             1.0,
@@ -64,6 +86,7 @@ impl Zone {
             self.color[1],
             self.color[2],
             borders as f32,
+            self.kind as i32 as f32,
         ];
 
         vertices
@@ -111,7 +134,7 @@ impl Zone {
                     2,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 8) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     0 as _,
                 );
                 gl::EnableVertexAttribArray(0);
@@ -121,7 +144,7 @@ impl Zone {
                     2,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 8) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     (2 * size_of::<f32>()) as _,
                 );
                 gl::EnableVertexAttribArray(1);
@@ -131,7 +154,7 @@ impl Zone {
                     3,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 8) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     (4 * size_of::<f32>()) as _,
                 );
                 gl::EnableVertexAttribArray(2);
@@ -141,10 +164,20 @@ impl Zone {
                     1,
                     gl::FLOAT,
                     gl::FALSE,
-                    (size_of::<f32>() * 8) as i32,
+                    (size_of::<f32>() * 9) as i32,
                     (7 * size_of::<f32>()) as _,
                 );
                 gl::EnableVertexAttribArray(3);
+
+                gl::VertexAttribPointer(
+                    4,
+                    1,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    (size_of::<f32>() * 9) as i32,
+                    (8 * size_of::<f32>()) as _,
+                );
+                gl::EnableVertexAttribArray(4);
             }
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl::BindVertexArray(0);
@@ -159,6 +192,7 @@ impl Zone {
         program: &Program<Shader>,
         len_vec_vertices: usize,
         vao: Vao,
+        daylight: f32,
     ) {
         if len_vec_vertices == 0 {
             return;
@@ -179,13 +213,18 @@ impl Zone {
                     camera.position.y,
                 );
                 gl::Uniform1f(get_location(program, "u_camera_scale"), camera.scale);
-                gl::DrawArrays(gl::TRIANGLES, 0, (len_vec_vertices / 8) as i32);
+                gl::Uniform1f(get_location(program, "u_daylight"), daylight);
+                gl::DrawArrays(gl::TRIANGLES, 0, (len_vec_vertices / 9) as i32);
                 gl::UseProgram(0);
             }
             gl::BindVertexArray(0);
         }
     }
 
+    pub fn blocks_cells(&self) -> bool {
+        self.kind.blocks_cells()
+    }
+
     fn checking_neighbors(pos: (i32, i32), zones: &Layout<Zone>) -> i32 {
         use nalgebra::clamp;
         let mut borders = 0;
@@ -228,3 +267,9 @@ impl Zone {
         borders
     }
 }
+
+impl Default for Zone {
+    fn default() -> Self {
+        Self::new(ZoneKind::default())
+    }
+}